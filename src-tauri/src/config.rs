@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+use serde::Deserialize;
+use tracing::info;
+
+use crate::transport::{FanoutMode, InterfaceSelector};
+
+/// 默认的配置文件，位于当前目录，与 logs 目录相同
+pub const DEFAULT_CONFIG_FILE: &str = "rid-simulator.json";
+
+/// 应用配置，从 JSON 配置文件读取，文件不存在时全部使用默认值
+///
+/// ```json
+/// { "interface": "wlan1,wlx00e04bd3ded6", "fanout": "round_robin" }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub interface: Option<String>, // 注入用网卡的名称或 MAC 地址，多块网卡用逗号分隔
+    pub fanout: Option<FanoutMode>, // 多块网卡时帧的分发方式
+}
+
+impl AppConfig {
+    /// 配置文件路径，可由环境变量 RID_CONFIG 指定
+    pub fn path() -> PathBuf {
+        match std::env::var_os("RID_CONFIG") {
+            Some(path) => PathBuf::from(path),
+            None => PathBuf::from(DEFAULT_CONFIG_FILE),
+        }
+    }
+
+    pub fn load() -> Result<Self, String> {
+        let path = Self::path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("读取配置文件失败 {}: {}", path.display(), e))?;
+        let config = Self::from_json(&text)
+            .map_err(|e| format!("配置文件格式错误 {}: {}", path.display(), e))?;
+        info!("config loaded: {}", path.display());
+        Ok(config)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+
+    /// 注入用的网卡，环境变量 RID_INTERFACE 优先于配置文件，都未指定时为空
+    pub fn interface_selectors(&self) -> Result<Vec<InterfaceSelector>, String> {
+        let value = match std::env::var("RID_INTERFACE") {
+            Ok(value) if !value.trim().is_empty() => Some(value),
+            _ => self.interface.clone(),
+        };
+        match value {
+            Some(value) => parse_selectors(&value),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// 帧的分发方式，环境变量 RID_FANOUT 优先于配置文件
+    pub fn fanout_mode(&self) -> Result<Option<FanoutMode>, String> {
+        match std::env::var("RID_FANOUT") {
+            Ok(value) if !value.trim().is_empty() => value.parse().map(Some),
+            _ => Ok(self.fanout),
+        }
+    }
+}
+
+/// 解析逗号分隔的网卡名称或 MAC 地址
+pub fn parse_selectors(value: &str) -> Result<Vec<InterfaceSelector>, String> {
+    value.split(',').map(str::parse).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_config_file() {
+        let config = AppConfig::from_json(r#"{ "interface": "wlan1, 00:e0:4b:d3:de:d6", "fanout": "broadcast" }"#).unwrap();
+        assert_eq!(config.fanout, Some(FanoutMode::Broadcast));
+        assert_eq!(
+            parse_selectors(config.interface.as_deref().unwrap()).unwrap(),
+            vec![
+                InterfaceSelector::Name("wlan1".into()),
+                "00:e0:4b:d3:de:d6".parse().unwrap(),
+            ]
+        );
+        assert_eq!(AppConfig::from_json("{}").unwrap(), AppConfig::default());
+        assert!(AppConfig::from_json(r#"{ "interface": 1 }"#).is_err());
+        assert!(AppConfig::from_json(r#"{ "fanout": "all" }"#).is_err());
+        assert!(parse_selectors("wlan1,,wlan2").is_err());
+    }
+}
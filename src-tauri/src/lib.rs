@@ -1,11 +1,15 @@
 pub mod rid_simulator;
 pub mod message;
 pub mod mqtt_manager;
+pub mod transport;
+pub mod config;
 
 use tracing::{info, error};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use tauri::Emitter;
 use crate::mqtt_manager::get_mqtt_manager;
+use crate::transport::{InterfaceInfo, TransportStats};
+use crate::transport::interfaces;
 
 #[tauri::command]
 async fn connect_to_mqtt_server(host: String, app_handle: tauri::AppHandle) -> Result<String, String> {
@@ -25,6 +29,29 @@ async fn get_connection_status() -> Result<bool, String> {
     Ok(manager.is_connected().await)
 }
 
+#[tauri::command]
+async fn list_interfaces() -> Result<Vec<InterfaceInfo>, String> {
+    Ok(interfaces::list_interfaces())
+}
+
+#[tauri::command]
+async fn select_interface(selector: String) -> Result<Vec<String>, String> {
+    let manager = get_mqtt_manager();
+    manager.select_interfaces(selector).await
+}
+
+#[tauri::command]
+async fn set_fanout_mode(mode: String) -> Result<String, String> {
+    let manager = get_mqtt_manager();
+    manager.set_fanout_mode(mode).await
+}
+
+#[tauri::command]
+async fn get_transport_stats() -> Result<Vec<TransportStats>, String> {
+    let manager = get_mqtt_manager();
+    Ok(manager.transport_stats().await)
+}
+
 #[tauri::command]
 async fn add_log_from_rust(message: String, app_handle: tauri::AppHandle) -> Result<(), String> {
     info!("Sending log message to frontend: {}", message);
@@ -95,6 +122,10 @@ pub fn run() {
             connect_to_mqtt_server, 
             disconnect_mqtt, 
             get_connection_status,
+            list_interfaces,
+            select_interface,
+            set_fanout_mode,
+            get_transport_stats,
             add_log_from_rust
         ])
         .run(tauri::generate_context!())
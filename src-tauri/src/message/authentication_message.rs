@@ -0,0 +1,347 @@
+use serde::{Serialize, Deserialize};
+
+use crate::message::message::MessageType;
+
+use super::describe::FieldNode;
+use super::message::{Message, MessageError};
+use super::protocol::{ProtocolVersion, WireFormat};
+use super::units::{decode_astm_timestamp, encode_astm_timestamp};
+
+/// 认证报文（报文类型 0x2），可选报文，认证数据按页分段发送
+///
+/// 第0页携带末页索引、认证数据总长度和时间戳，其余页只携带认证数据。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthenticationMessage {
+    pub auth_type: u8,          // 认证类型 (7-4位)
+    pub page_number: u8,        // 页索引 (3-0位)
+    #[serde(default)]
+    pub last_page_index: u8,    // 末页索引 (仅第0页)
+    #[serde(default)]
+    pub length: u8,             // 认证数据总长度 (仅第0页)
+    #[serde(default)]
+    pub timestamp: u32,         // 时间戳 (Unix时间, 秒, 仅第0页, ASTM 线上为自 2019-01-01 起的秒数)
+    pub auth_data: Vec<u8>,     // 本页认证数据 (第0页17字节, 其余页23字节)
+}
+
+impl AuthenticationMessage {
+    pub const MESSAGE_TYPE: u8 = 0x02;
+    const EXPECTED_LENGTH: usize = 24;
+    // 第0页可携带的认证数据长度
+    const FIRST_PAGE_DATA_LENGTH: usize = 17;
+    // 其余页可携带的认证数据长度
+    const PAGE_DATA_LENGTH: usize = 23;
+    // 总长度字段为1字节
+    pub const MAX_AUTH_DATA_LENGTH: usize = u8::MAX as usize;
+
+    /// 将认证数据按页拆分为多条认证报文，按默认协议版本的页数上限
+    ///
+    /// # 错误
+    /// 认证数据超过 255 字节时返回 MessageError::DataTooLong
+    pub fn paginate(auth_type: u8, timestamp: u32, data: &[u8]) -> Result<Vec<Self>, MessageError> {
+        Self::paginate_for(ProtocolVersion::default(), auth_type, timestamp, data)
+    }
+
+    /// 按指定协议版本的页数上限拆分认证数据
+    ///
+    /// # 错误
+    /// 认证数据超过该版本可携带的长度时返回 MessageError::DataTooLong
+    pub fn paginate_for(version: ProtocolVersion, auth_type: u8, timestamp: u32, data: &[u8]) -> Result<Vec<Self>, MessageError> {
+        let max_length = Self::max_data_length(version);
+        if data.len() > max_length {
+            return Err(MessageError::DataTooLong(max_length, data.len()));
+        }
+
+        let first_len = data.len().min(Self::FIRST_PAGE_DATA_LENGTH);
+        let mut chunks = vec![&data[..first_len]];
+        chunks.extend(data[first_len..].chunks(Self::PAGE_DATA_LENGTH));
+        let last_page_index = (chunks.len() - 1) as u8;
+
+        let pages = chunks
+            .into_iter()
+            .enumerate()
+            .map(|(page_number, chunk)| {
+                let capacity = Self::page_capacity(page_number as u8);
+                let mut auth_data = chunk.to_vec();
+                auth_data.resize(capacity, 0);
+                Self {
+                    auth_type,
+                    page_number: page_number as u8,
+                    last_page_index: if page_number == 0 { last_page_index } else { 0 },
+                    length: if page_number == 0 { data.len() as u8 } else { 0 },
+                    timestamp: if page_number == 0 { timestamp } else { 0 },
+                    auth_data,
+                }
+            })
+            .collect();
+        Ok(pages)
+    }
+
+    /// 将按页排列的认证报文还原为完整认证数据，长度以第0页记录的总长度为准
+    pub fn reassemble(pages: &[Self]) -> Vec<u8> {
+        let mut sorted: Vec<&Self> = pages.iter().collect();
+        sorted.sort_by_key(|page| page.page_number);
+
+        let length = sorted
+            .first()
+            .filter(|page| page.page_number == 0)
+            .map(|page| page.length as usize)
+            .unwrap_or(0);
+        let mut data: Vec<u8> = sorted.iter().flat_map(|page| page.auth_data.iter().copied()).collect();
+        data.truncate(length);
+        data
+    }
+
+    /// 指定协议版本下认证数据的最大长度
+    pub fn max_data_length(version: ProtocolVersion) -> usize {
+        let pages = version.max_auth_pages();
+        (Self::FIRST_PAGE_DATA_LENGTH + (pages - 1) * Self::PAGE_DATA_LENGTH)
+            .min(Self::MAX_AUTH_DATA_LENGTH)
+    }
+
+    // 指定页可携带的认证数据长度
+    fn page_capacity(page_number: u8) -> usize {
+        if page_number == 0 {
+            Self::FIRST_PAGE_DATA_LENGTH
+        } else {
+            Self::PAGE_DATA_LENGTH
+        }
+    }
+}
+
+impl Message for AuthenticationMessage {
+    /// 从 u8 数组解析为结构化数据
+    ///
+    /// # 参数
+    /// - `data`: 至少包含 24 字节的输入数据
+    ///
+    /// # 错误
+    /// - 当输入数据长度不足时返回 ParseError::InsufficientLength
+    fn from_bytes_with(data: &[u8], format: &WireFormat) -> Result<Self, MessageError> {
+        if data.len() < Self::EXPECTED_LENGTH {
+            return Err(MessageError::InsufficientLength(
+                Self::EXPECTED_LENGTH,
+                data.len()
+            ));
+        }
+
+        // 解析第一个字节：认证类型(高4位) + 页索引(低4位)
+        let byte0 = data[0];
+        let auth_type = (byte0 >> 4) & 0x0F;
+        let page_number = byte0 & 0x0F;
+
+        if page_number == 0 {
+            let last_page_index = data[1];
+            let length = data[2];
+            let timestamp = u32::from_le_bytes([data[3], data[4], data[5], data[6]]);
+            let timestamp = if format.is_astm() { decode_astm_timestamp(timestamp) } else { timestamp };
+            let auth_data = data[7..7 + Self::FIRST_PAGE_DATA_LENGTH].to_vec();
+            Ok(Self {
+                auth_type,
+                page_number,
+                last_page_index,
+                length,
+                timestamp,
+                auth_data,
+            })
+        } else {
+            let auth_data = data[1..1 + Self::PAGE_DATA_LENGTH].to_vec();
+            Ok(Self {
+                auth_type,
+                page_number,
+                last_page_index: 0,
+                length: 0,
+                timestamp: 0,
+                auth_data,
+            })
+        }
+    }
+
+    fn encode_with(&self, format: &WireFormat) -> Result<Vec<u8>, MessageError> {
+        let mut bytes: Vec<u8> = Vec::new();
+
+        let message_protocol = format.header(MessageType::AuthenticationMessageType);
+        bytes.push(message_protocol);
+
+        // 编码第一个字节：认证类型(高4位) + 页索引(低4位)，超出4位时拒绝编码
+        if self.auth_type > 0x0F {
+            return Err(MessageError::InvalidFieldValue("auth_type", self.auth_type));
+        }
+        if self.page_number > 0x0F {
+            return Err(MessageError::InvalidFieldValue("page_number", self.page_number));
+        }
+        let page_number = self.page_number;
+        bytes.push((self.auth_type << 4) | page_number);
+
+        if page_number == 0 {
+            bytes.push(self.last_page_index);
+            bytes.push(self.length);
+            let timestamp = if format.is_astm() { encode_astm_timestamp(self.timestamp) } else { self.timestamp };
+            bytes.extend_from_slice(&timestamp.to_le_bytes());
+        }
+
+        // 认证数据不足本页容量时补0，超出时拒绝编码，避免丢失认证数据
+        let capacity = Self::page_capacity(page_number);
+        if self.auth_data.len() > capacity {
+            return Err(MessageError::DataTooLong(capacity, self.auth_data.len()));
+        }
+        bytes.extend_from_slice(&self.auth_data);
+        bytes.resize(bytes.len() + capacity - self.auth_data.len(), 0);
+
+        Ok(bytes)
+    }
+
+    fn validate(&self) -> Result<(), MessageError> {
+        let capacity = Self::page_capacity(self.page_number);
+        if self.auth_data.len() > capacity {
+            return Err(MessageError::DataTooLong(capacity, self.auth_data.len()));
+        }
+        if (6..=9).contains(&self.auth_type) {
+            return Err(MessageError::FieldOutOfRange {
+                field: "auth_type", offset: 1, value: self.auth_type as i64, allowed: "0-5, 10-15",
+            });
+        }
+        if self.page_number == 0 && self.last_page_index > 15 {
+            return Err(MessageError::FieldOutOfRange {
+                field: "last_page_index", offset: 2, value: self.last_page_index as i64, allowed: "0-15",
+            });
+        }
+        Ok(())
+    }
+
+    fn describe(&self) -> FieldNode {
+        let mut node = FieldNode::group("authentication_message", "认证报文")
+            .child(FieldNode::field("auth_type", "认证类型", self.auth_type))
+            .child(FieldNode::field("page_number", "页索引", self.page_number));
+        if self.page_number == 0 {
+            node = node
+                .child(FieldNode::field("last_page_index", "末页索引", self.last_page_index))
+                .child(FieldNode::field("length", "认证数据总长度", format!("{} 字节", self.length)))
+                .child(FieldNode::field("timestamp", "时间戳", self.timestamp).raw(self.timestamp));
+        }
+        node.child(FieldNode::field("auth_data", "认证数据", format!("{} 字节", self.auth_data.len())).raw_bytes(&self.auth_data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // 2025-01-01T00:00:00Z，晚于 ASTM 时间起点，两种编码风格都能还原
+    const TIMESTAMP: u32 = 1_735_689_600;
+
+    fn data(length: usize) -> Vec<u8> {
+        (0..length).map(|index| index as u8).collect()
+    }
+
+    fn formats() -> [WireFormat; 2] {
+        [WireFormat::default(), WireFormat::astm(ProtocolVersion::F3411_22a)]
+    }
+
+    #[test]
+    fn round_trips_payload_filling_first_page() {
+        let pages = AuthenticationMessage::paginate(1, TIMESTAMP, &data(17)).unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].last_page_index, 0);
+        assert_eq!(AuthenticationMessage::reassemble(&pages), data(17));
+    }
+
+    #[test]
+    fn round_trips_payload_spanning_pages() {
+        // 17 + 23 字节正好填满两页，再多一字节需要第三页
+        let pages = AuthenticationMessage::paginate(1, TIMESTAMP, &data(40)).unwrap();
+        assert_eq!(pages.len(), 2);
+        let pages = AuthenticationMessage::paginate(1, TIMESTAMP, &data(41)).unwrap();
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[0].last_page_index, 2);
+        assert_eq!(AuthenticationMessage::reassemble(&pages), data(41));
+
+        let mut reversed = pages.clone();
+        reversed.reverse();
+        assert_eq!(AuthenticationMessage::reassemble(&reversed), data(41));
+    }
+
+    #[test]
+    fn round_trips_maximum_payload() {
+        // 总长度字段为1字节，255 字节需要 12 页，未超出 16 页的上限
+        let max_length = AuthenticationMessage::max_data_length(ProtocolVersion::F3411_22a);
+        assert_eq!(max_length, 255);
+        let pages = AuthenticationMessage::paginate_for(ProtocolVersion::F3411_22a, 1, TIMESTAMP, &data(max_length)).unwrap();
+        assert_eq!(pages.len(), 12);
+        assert!(pages.len() <= ProtocolVersion::F3411_22a.max_auth_pages());
+        assert_eq!(AuthenticationMessage::reassemble(&pages), data(max_length));
+        assert_eq!(
+            AuthenticationMessage::paginate_for(ProtocolVersion::F3411_22a, 1, TIMESTAMP, &data(max_length + 1)),
+            Err(MessageError::DataTooLong(255, 256))
+        );
+
+        // F3411-19 最多 5 页，17 + 4 * 23 字节正好填满
+        let pages = AuthenticationMessage::paginate_for(ProtocolVersion::F3411_19, 1, TIMESTAMP, &data(109)).unwrap();
+        assert_eq!(pages.len(), 5);
+        assert_eq!(AuthenticationMessage::reassemble(&pages), data(109));
+        assert_eq!(
+            AuthenticationMessage::paginate_for(ProtocolVersion::F3411_19, 1, TIMESTAMP, &data(110)),
+            Err(MessageError::DataTooLong(109, 110))
+        );
+    }
+
+    #[test]
+    fn first_and_last_pages_round_trip_in_both_profiles() {
+        let first = AuthenticationMessage {
+            auth_type: 1,
+            page_number: 0,
+            last_page_index: 15,
+            length: 255,
+            timestamp: TIMESTAMP,
+            auth_data: data(17),
+        };
+        let last = AuthenticationMessage {
+            auth_type: 1,
+            page_number: 15,
+            last_page_index: 0,
+            length: 0,
+            timestamp: 0,
+            auth_data: data(23),
+        };
+        for format in formats() {
+            for page in [&first, &last] {
+                let encoded = page.encode_with(&format).unwrap();
+                assert_eq!(encoded.len(), 25);
+                let decoded = AuthenticationMessage::from_bytes_with(&encoded[1..], &format).unwrap();
+                assert_eq!(&decoded, page);
+                assert_eq!(decoded.encode_with(&format).unwrap(), encoded);
+            }
+        }
+    }
+
+    #[test]
+    fn pads_short_data_and_rejects_overlong_data() {
+        let page = AuthenticationMessage::paginate(1, TIMESTAMP, &data(3)).unwrap().remove(0);
+        let short = AuthenticationMessage { auth_data: data(3), ..page.clone() };
+        let encoded = short.encode().unwrap();
+        assert_eq!(&encoded[8..], &[&data(3)[..], &[0; 14]].concat()[..]);
+
+        let overlong = AuthenticationMessage { auth_data: data(18), ..page };
+        assert_eq!(overlong.encode(), Err(MessageError::DataTooLong(17, 18)));
+        assert_eq!(overlong.validate(), Err(MessageError::DataTooLong(17, 18)));
+        let overlong = AuthenticationMessage { page_number: 1, auth_data: data(24), ..overlong };
+        assert_eq!(overlong.encode(), Err(MessageError::DataTooLong(23, 24)));
+    }
+
+    proptest! {
+        #[test]
+        fn paginated_pages_round_trip(data in prop::collection::vec(any::<u8>(), 0..=255), astm in any::<bool>()) {
+            let format = if astm { WireFormat::astm(ProtocolVersion::F3411_22a) } else { WireFormat::default() };
+            let pages = AuthenticationMessage::paginate_for(format.version, 1, TIMESTAMP, &data).unwrap();
+            let decoded: Vec<AuthenticationMessage> = pages
+                .iter()
+                .map(|page| {
+                    let encoded = page.encode_with(&format).unwrap();
+                    AuthenticationMessage::from_bytes_with(&encoded[1..], &format).unwrap()
+                })
+                .collect();
+            prop_assert_eq!(&decoded, &pages);
+            prop_assert_eq!(AuthenticationMessage::reassemble(&decoded), data);
+        }
+    }
+}
@@ -1,16 +1,20 @@
 use std::str;
-use tracing::info;
+use tracing::{info, trace};
 use serde::{Serialize, Deserialize};
 
+use crate::message::field_types::{check_coded, IdType, UaType};
 use crate::message::message::MessageType;
 
+use super::describe::FieldNode;
 use super::message::{Message, MessageError};
+use super::protocol::WireFormat;
+use super::uas_id::{self, MAX_UAS_ID_LENGTH};
 
 /// 基本类型，主要包含了RID的字符串
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BaseMessage {
-    pub id_type: u8,          // 高位 4 位 (7-4 位)
-    pub ua_type: u8,          // 低位 4 位 (3-0 位)
+    pub id_type: IdType,      // 高位 4 位 (7-4 位)
+    pub ua_type: UaType,      // 低位 4 位 (3-0 位)
     pub uas_id: String,       // UAS 识别身份信息（字符串类型）
     #[serde(default)]
     pub reserved: [u8; 3],    // 3 字节预留空间
@@ -19,6 +23,22 @@ pub struct BaseMessage {
 impl BaseMessage {
     pub const MESSAGE_TYPE: u8 = 0x00;
     const EXPECTED_LENGTH: usize = 24;
+    const UUID_LENGTH: usize = 16;
+
+    /// 创建基本报文，按 ID 类型校验 UAS ID
+    ///
+    /// # 错误
+    /// UAS ID 不符合该 ID 类型的格式时返回 MessageError::InvalidUasId 或 MessageError::DataTooLong
+    pub fn new(id_type: IdType, ua_type: UaType, uas_id: impl Into<String>) -> Result<Self, MessageError> {
+        let uas_id = uas_id.into();
+        uas_id::validate_uas_id(id_type, &uas_id)?;
+        Ok(Self {
+            id_type,
+            ua_type,
+            uas_id,
+            reserved: [0; 3],
+        })
+    }
 }
 
 impl Message for BaseMessage {
@@ -30,7 +50,9 @@ impl Message for BaseMessage {
     /// # 错误
     /// - 当输入数据长度不足时返回 ParseError::InsufficientLength
     /// - 当 UAS ID 不是有效的 UTF-8 时返回 ParseError::InvalidUtf8
-    fn from_bytes(data: &[u8]) -> Result<Self, MessageError> {
+    ///
+    /// ID 类型为 UTM 分配时，前 16 字节按 UUID 解析为文本
+    fn from_bytes_with(data: &[u8], _format: &WireFormat) -> Result<Self, MessageError> {
         if data.len() < Self::EXPECTED_LENGTH{
             return Err(MessageError::InsufficientLength(
                 Self::EXPECTED_LENGTH, 
@@ -40,31 +62,36 @@ impl Message for BaseMessage {
 
         // 解析第一个字节 (起始字节 1)
         let byte0 = data[0];
-        let id_type = (byte0 >> 4) & 0x0F;  // 提取高4位 (7-4位)
-        let ua_type = byte0 & 0x0F;         // 提取低4位 (3-0位)
-        info!("id type={}, ua_type={}", id_type, ua_type);
+        let id_type = IdType::from_bits((byte0 >> 4) & 0x0F);  // 提取高4位 (7-4位)
+        let ua_type = UaType::from_bits(byte0 & 0x0F);         // 提取低4位 (3-0位)
+        trace!("id type={:?}, ua_type={:?}", id_type, ua_type);
         // 解析 UAS ID (起始字节 2，长度 20)
         let uas_id_start = 1;
-        let uas_id_bytes = &data[uas_id_start..uas_id_start + 20];
+        let uas_id_bytes = &data[uas_id_start..uas_id_start + MAX_UAS_ID_LENGTH];
         
-        // 转换为 String，移除尾部的空字符(\0)和空白字符
-        let uas_id = match str::from_utf8(uas_id_bytes) {
-            Ok(s) => {
-                // 移除尾部的空字符和空白字符
-                s.trim_end_matches('\0')
-                 .trim_end()
-                 .to_string()
-            },
-            Err(e) => {
-                info!("base message utf8 error.");
-                return Err(MessageError::InvalidUtf8(e))
+        // UTM 分配的 ID 为 16 字节 UUID，其余类型转换为 String，移除尾部的空字符(\0)和空白字符
+        let uas_id = if id_type == IdType::UtmAssigned {
+            let mut uuid = [0u8; Self::UUID_LENGTH];
+            uuid.copy_from_slice(&uas_id_bytes[..Self::UUID_LENGTH]);
+            uas_id::format_uuid(&uuid)
+        } else {
+            match str::from_utf8(uas_id_bytes) {
+                Ok(s) => {
+                    // 移除尾部的空字符和空白字符
+                    s.trim_end_matches('\0')
+                     .trim_end()
+                     .to_string()
+                },
+                Err(e) => {
+                    info!("base message utf8 error.");
+                    return Err(MessageError::InvalidUtf8(e))
+                }
             }
         };
 
         // 解析预留字段 (起始字节 22)
-        //let reserved_start = 21;  // 起始索引 = 起始字节 - 1
-        let reserved = [0,0,0];
-            
+        let reserved_start = 21;  // 起始索引 = 起始字节 - 1
+        let reserved = [data[reserved_start], data[reserved_start + 1], data[reserved_start + 2]];
 
         Ok(Self {
             id_type,
@@ -74,35 +101,143 @@ impl Message for BaseMessage {
         })
     }
 
-    fn encode(&self) -> Vec<u8> {
+    fn encode_with(&self, format: &WireFormat) -> Result<Vec<u8>, MessageError> {
         let mut bytes:Vec<u8> = Vec::new();
         
-        let message_type = MessageType::BaseMessageType as u8;
-        let message_protocol = (message_type << 4) | 0x01;
+        let message_protocol = format.header(MessageType::BaseMessageType);
         bytes.push(message_protocol);
         // 编码第一个字节：id_type（高4位） + ua_type（低4位）
-        let type_byte = (self.id_type << 4) | (self.ua_type & 0x0F);
+        let type_byte = (u8::from(self.id_type) << 4) | (u8::from(self.ua_type) & 0x0F);
         bytes.push(type_byte);
         
-        // 编码UAS ID（最多20字节）
-        let uas_bytes = self.uas_id.as_bytes().to_vec();
+        // 编码UAS ID（最多20字节，UTM 分配的 UUID 按 16 字节二进制写入）
+        // 截断后的 ID 会变成另一架航空器的身份，超长时拒绝编码
+        let mut uas_bytes = [0u8; MAX_UAS_ID_LENGTH];
+        match uas_id::parse_uuid(&self.uas_id) {
+            Some(uuid) if self.id_type == IdType::UtmAssigned => {
+                uas_bytes[..Self::UUID_LENGTH].copy_from_slice(&uuid);
+            },
+            _ => {
+                let id_bytes = self.uas_id.as_bytes();
+                if id_bytes.len() > MAX_UAS_ID_LENGTH {
+                    return Err(MessageError::DataTooLong(MAX_UAS_ID_LENGTH, id_bytes.len()));
+                }
+                uas_bytes[..id_bytes.len()].copy_from_slice(id_bytes);
+            },
+        }
         bytes.extend_from_slice(&uas_bytes);
+
+        // 预留字段
+        bytes.extend_from_slice(&self.reserved);
         
-        //不足的位置写0
-        let id_len = uas_bytes.len();
-        let reserved = vec![0u8; 23-id_len];
-        bytes.extend(&reserved);
-        
-        bytes
+        Ok(bytes)
     }
 
+    fn validate(&self) -> Result<(), MessageError> {
+        check_coded(self.id_type, 1)?;
+        check_coded(self.ua_type, 1)?;
+        uas_id::validate_uas_id(self.id_type, &self.uas_id)
+    }
 
-    fn print(&self) {
-        println!("=== BaseMessage ===");
-        println!("ID 类型: 0x{:X}", self.id_type);
-        println!("UA 类型: 0x{:X}", self.ua_type);
-        println!("UAS ID: '{}'", self.uas_id);
-        println!("预留字段: {:02X?}", self.reserved);
+    fn describe(&self) -> FieldNode {
+        FieldNode::group("base_message", "基本报文")
+            .child(FieldNode::field("id_type", "ID 类型", format!("{:?}", self.id_type)).raw(u8::from(self.id_type)))
+            .child(FieldNode::field("ua_type", "UA 类型", format!("{:?}", self.ua_type)).raw(u8::from(self.ua_type)))
+            .child(FieldNode::field("uas_id", "UAS ID", &self.uas_id).raw_bytes(self.uas_id.as_bytes()))
+            .child(FieldNode::group("reserved", "预留字段").raw_bytes(&self.reserved))
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const SAMPLE_FRAME: &str = "0112313538314635464b443232393430303041314232000000";
+
+    fn sample() -> BaseMessage {
+        BaseMessage {
+            id_type: IdType::SerialNumber,
+            ua_type: UaType::HelicopterOrMultirotor,
+            uas_id: "1581F5FKD2294000A1B2".to_string(),
+            reserved: [0; 3],
+        }
+    }
+
+    #[test]
+    fn encodes_sample_frame() {
+        assert_eq!(hex::encode(sample().encode().unwrap()), SAMPLE_FRAME);
+    }
+
+    #[test]
+    fn decodes_sample_frame() {
+        let frame = hex::decode(SAMPLE_FRAME).unwrap();
+        assert_eq!(BaseMessage::from_bytes(&frame[1..]).unwrap(), sample());
+    }
+
+    #[test]
+    fn full_length_id_round_trips() {
+        let message = BaseMessage { uas_id: "ABCDEFGHIJ0123456789".to_string(), ..sample() };
+        let encoded = message.encode().unwrap();
+        assert_eq!(encoded.len(), 25);
+        assert_eq!(BaseMessage::from_bytes(&encoded[1..]).unwrap(), message);
+    }
+
+    #[test]
+    fn rejects_overlong_id_instead_of_panicking() {
+        let message = BaseMessage { uas_id: "ABCDEFGHIJ0123456789XYZ".to_string(), ..sample() };
+        assert_eq!(message.encode(), Err(MessageError::DataTooLong(20, 23)));
+        assert_eq!(message.validate(), Err(MessageError::DataTooLong(20, 23)));
+    }
+
+    #[test]
+    fn utm_uuid_is_encoded_as_binary() {
+        let message = BaseMessage::new(
+            IdType::UtmAssigned,
+            UaType::Aeroplane,
+            "123e4567-e89b-12d3-a456-426614174000",
+        ).unwrap();
+        let encoded = message.encode().unwrap();
+        assert_eq!(&encoded[2..18], hex::decode("123e4567e89b12d3a456426614174000").unwrap().as_slice());
+        assert_eq!(&encoded[18..22], &[0u8; 4]);
+        assert_eq!(BaseMessage::from_bytes(&encoded[1..]).unwrap(), message);
+    }
+
+    #[test]
+    fn new_rejects_invalid_serial_number() {
+        assert!(matches!(
+            BaseMessage::new(IdType::SerialNumber, UaType::HelicopterOrMultirotor, "1581F5FKD229400"),
+            Err(MessageError::InvalidUasId(_, _))
+        ));
+        assert_eq!(sample().validate(), Ok(()));
+    }
+
+    #[test]
+    fn rejects_short_input() {
+        assert_eq!(
+            BaseMessage::from_bytes(&[0u8; 23]),
+            Err(MessageError::InsufficientLength(24, 23))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips(
+            id_type in (0u8..16).prop_filter("UTM 分配的 ID 按 UUID 编码", |&t| t != 3),
+            ua_type in 0u8..16,
+            uas_id in "[A-Z0-9]{0,20}",
+            reserved in any::<[u8; 3]>(),
+        ) {
+            let message = BaseMessage {
+                id_type: IdType::from_bits(id_type),
+                ua_type: UaType::from_bits(ua_type),
+                uas_id,
+                reserved,
+            };
+            let encoded = message.encode().unwrap();
+            prop_assert_eq!(encoded.len(), 25);
+            prop_assert_eq!(BaseMessage::from_bytes(&encoded[1..]).unwrap(), message);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+/// 单字节内的位段，由起始位和位宽描述，编码和解码共用同一份定义
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BitField {
+    shift: u8,
+    width: u8,
+}
+
+impl BitField {
+    pub(crate) const fn new(shift: u8, width: u8) -> Self {
+        assert!(width > 0 && shift + width <= 8, "位段超出单字节范围");
+        Self { shift, width }
+    }
+
+    /// 位段在字节内的掩码
+    pub(crate) const fn mask(self) -> u8 {
+        (((1u16 << self.width) - 1) as u8) << self.shift
+    }
+
+    /// 读取位段的值
+    pub(crate) const fn get(self, byte: u8) -> u8 {
+        (byte & self.mask()) >> self.shift
+    }
+
+    /// 写入位段的值，超出位宽的高位被丢弃
+    pub(crate) fn set(self, byte: &mut u8, value: u8) {
+        *byte = (*byte & !self.mask()) | ((value << self.shift) & self.mask());
+    }
+
+    /// 检查一组位段互不重叠，在常量上下文中使用时于编译期报错
+    pub(crate) const fn assert_disjoint(fields: &[BitField]) {
+        let mut used = 0u8;
+        let mut i = 0;
+        while i < fields.len() {
+            assert!(used & fields[i].mask() == 0, "位段重叠");
+            used |= fields[i].mask();
+            i += 1;
+        }
+    }
+}
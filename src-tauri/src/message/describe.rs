@@ -0,0 +1,119 @@
+use std::fmt;
+use serde::Serialize;
+
+/// 报文的字段描述树，同时给出解码后的取值和线上的原始取值，
+/// 用于日志、JSON 导出和界面展示
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldNode {
+    pub key: &'static str,     // 字段名，与 JSON 字段名一致
+    pub label: &'static str,   // 中文标签
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>, // 解码后的取值，物理量带单位
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,   // 线上的原始取值 (十六进制)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<FieldNode>,
+}
+
+impl FieldNode {
+    /// 分组节点，如一条报文或整包
+    pub fn group(key: &'static str, label: &'static str) -> Self {
+        Self {
+            key,
+            label,
+            value: None,
+            raw: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// 叶子节点
+    pub fn field(key: &'static str, label: &'static str, value: impl fmt::Display) -> Self {
+        Self {
+            value: Some(value.to_string()),
+            ..Self::group(key, label)
+        }
+    }
+
+    /// 设置原始取值，整数按线上宽度输出十六进制
+    pub fn raw(mut self, raw: impl fmt::UpperHex) -> Self {
+        self.raw = Some(format!("0x{:X}", raw));
+        self
+    }
+
+    /// 设置原始字节
+    pub fn raw_bytes(mut self, bytes: &[u8]) -> Self {
+        self.raw = Some(hex::encode_upper(bytes));
+        self
+    }
+
+    pub fn child(mut self, child: FieldNode) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn children(mut self, children: impl IntoIterator<Item = FieldNode>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// 按字段名查找直接子节点
+    pub fn get(&self, key: &str) -> Option<&FieldNode> {
+        self.children.iter().find(|child| child.key == key)
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", self.label, indent = depth * 2)?;
+        if let Some(value) = &self.value {
+            write!(f, ": {}", value)?;
+        }
+        if let Some(raw) = &self.raw {
+            write!(f, " [{}]", raw)?;
+        }
+        for child in &self.children {
+            writeln!(f)?;
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// 按缩进输出的多行文本
+impl fmt::Display for FieldNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> FieldNode {
+        FieldNode::group("base", "基本报文")
+            .child(FieldNode::field("uas_id", "UAS ID", "1581F5FKD2294000A1B2"))
+            .child(FieldNode::field("latitude", "纬度", "39.9000000°").raw(399_000_000i32))
+            .child(FieldNode::group("reserved", "预留字段").raw_bytes(&[0, 0x0a, 0xff]))
+    }
+
+    #[test]
+    fn displays_indented_tree() {
+        assert_eq!(
+            sample().to_string(),
+            "基本报文\n  UAS ID: 1581F5FKD2294000A1B2\n  纬度: 39.9000000° [0x17C841C0]\n  预留字段 [000AFF]"
+        );
+    }
+
+    #[test]
+    fn serializes_without_empty_parts() {
+        let json = serde_json::to_value(sample()).unwrap();
+        assert_eq!(json["children"][0], serde_json::json!({"key": "uas_id", "label": "UAS ID", "value": "1581F5FKD2294000A1B2"}));
+        assert_eq!(json["children"][1]["raw"], "0x17C841C0");
+        assert!(json.get("value").is_none());
+    }
+
+    #[test]
+    fn negative_raw_uses_wire_width() {
+        assert_eq!(FieldNode::field("vertical_speed", "垂直速度", "-1.5 m/s").raw(-3i8).raw.unwrap(), "0xFD");
+    }
+}
@@ -0,0 +1,225 @@
+use serde::{Serialize, Deserialize};
+
+use super::message::MessageError;
+
+/// 按位编码的字段枚举的公共接口
+pub trait CodedField: Copy {
+    /// 字段名，用于错误信息
+    const FIELD: &'static str;
+    /// 标准定义的取值
+    const ALLOWED: &'static str;
+
+    fn bits(self) -> u8;
+    fn is_known(self) -> bool;
+}
+
+/// 严格模式下校验编码字段是否为标准定义的取值
+pub fn check_coded<T: CodedField>(value: T, offset: usize) -> Result<(), MessageError> {
+    if value.is_known() {
+        Ok(())
+    } else {
+        Err(MessageError::FieldOutOfRange {
+            field: T::FIELD,
+            offset,
+            value: value.bits() as i64,
+            allowed: T::ALLOWED,
+        })
+    }
+}
+
+/// 定义按位编码的字段枚举
+///
+/// 解码时遇到标准未定义的取值保留为 `Unknown(u8)`，
+/// 而 JSON 反序列化只接受标准定义的取值，用于拦截格式错误的 MQTT 输入。
+macro_rules! coded_field {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident($field:literal) {
+            $( $(#[$variant_meta:meta])* $variant:ident = $value:literal, )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[serde(try_from = "u8", into = "u8")]
+        pub enum $name {
+            $( $(#[$variant_meta])* $variant, )*
+            /// 标准未定义的取值
+            Unknown(u8),
+        }
+
+        impl $name {
+            /// 字段名，用于错误信息
+            pub const FIELD: &'static str = $field;
+
+            /// 从线上取值解析，未定义的取值保留为 Unknown
+            pub fn from_bits(value: u8) -> Self {
+                match value {
+                    $( $value => Self::$variant, )*
+                    other => Self::Unknown(other),
+                }
+            }
+
+            /// 线上取值
+            pub fn bits(self) -> u8 {
+                match self {
+                    $( Self::$variant => $value, )*
+                    Self::Unknown(value) => value,
+                }
+            }
+
+            /// 是否为标准定义的取值
+            pub fn is_known(self) -> bool {
+                !matches!(Self::from_bits(self.bits()), Self::Unknown(_))
+            }
+        }
+
+        impl CodedField for $name {
+            const FIELD: &'static str = $field;
+            const ALLOWED: &'static str = stringify!($($value),*);
+
+            fn bits(self) -> u8 {
+                $name::bits(self)
+            }
+
+            fn is_known(self) -> bool {
+                $name::is_known(self)
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::from_bits(0)
+            }
+        }
+
+        impl From<$name> for u8 {
+            fn from(value: $name) -> u8 {
+                value.bits()
+            }
+        }
+
+        impl TryFrom<u8> for $name {
+            type Error = MessageError;
+
+            fn try_from(value: u8) -> Result<Self, Self::Error> {
+                match Self::from_bits(value) {
+                    Self::Unknown(value) => Err(MessageError::InvalidFieldValue(Self::FIELD, value)),
+                    known => Ok(known),
+                }
+            }
+        }
+    };
+}
+
+coded_field! {
+    /// UAS ID 类型
+    pub enum IdType("id_type") {
+        None = 0,
+        SerialNumber = 1,      // 产品序列号 (ANSI/CTA-2063-A)
+        CaaRegistration = 2,   // 民航局登记号
+        UtmAssigned = 3,       // UTM 分配的 UUID
+        SpecificSession = 4,   // 特定会话 ID
+    }
+}
+
+coded_field! {
+    /// 无人驾驶航空器类型
+    pub enum UaType("ua_type") {
+        None = 0,
+        Aeroplane = 1,              // 固定翼
+        HelicopterOrMultirotor = 2, // 直升机或多旋翼
+        Gyroplane = 3,              // 旋翼机
+        HybridLift = 4,             // 垂直起降固定翼
+        Ornithopter = 5,            // 扑翼机
+        Glider = 6,                 // 滑翔机
+        Kite = 7,                   // 风筝
+        FreeBalloon = 8,            // 自由气球
+        CaptiveBalloon = 9,         // 系留气球
+        Airship = 10,               // 飞艇
+        FreeFallParachute = 11,     // 无动力降落伞
+        Rocket = 12,                // 火箭
+        TetheredPoweredAircraft = 13, // 系留动力飞行器
+        GroundObstacle = 14,        // 地面障碍物
+        Other = 15,                 // 其他
+    }
+}
+
+coded_field! {
+    /// 运行状态
+    pub enum RunStatus("run_status") {
+        Undeclared = 0,            // 未声明
+        Ground = 1,                // 地面
+        Airborne = 2,              // 空中
+        Emergency = 3,             // 紧急
+        RemoteIdSystemFailure = 4, // 远程识别系统故障
+    }
+}
+
+coded_field! {
+    /// 高度类型
+    pub enum HeightType("height_type") {
+        AboveTakeoff = 0,     // 相对起飞点
+        AboveGroundLevel = 1, // 相对地面
+    }
+}
+
+coded_field! {
+    /// 水平精度
+    pub enum HorizontalAccuracy("horizontal_accuracy") {
+        Unavailable = 0,     // 未知或 ≥ 18.52 km
+        LessThan18520m = 1,
+        LessThan7408m = 2,
+        LessThan3704m = 3,
+        LessThan1852m = 4,
+        LessThan926m = 5,
+        LessThan556m = 6,
+        LessThan185m = 7,
+        LessThan93m = 8,
+        LessThan30m = 9,
+        LessThan10m = 10,
+        LessThan3m = 11,
+        LessThan1m = 12,
+    }
+}
+
+coded_field! {
+    /// 垂直精度
+    pub enum VerticalAccuracy("vertical_accuracy") {
+        Unavailable = 0,     // 未知或 ≥ 150 m
+        LessThan150m = 1,
+        LessThan45m = 2,
+        LessThan25m = 3,
+        LessThan10m = 4,
+        LessThan3m = 5,
+        LessThan1m = 6,
+    }
+}
+
+coded_field! {
+    /// 速度精度
+    pub enum SpeedAccuracy("speed_accuracy") {
+        Unavailable = 0,      // 未知或 ≥ 10 m/s
+        LessThan10mps = 1,
+        LessThan3mps = 2,
+        LessThan1mps = 3,
+        LessThan0Point3mps = 4,
+    }
+}
+
+coded_field! {
+    /// 控制站位置类型
+    pub enum StationType("station_type") {
+        TakeOff = 0,  // 起飞点
+        LiveGnss = 1, // 实时 GNSS
+        Fixed = 2,    // 固定位置
+    }
+}
+
+coded_field! {
+    /// 等级分类归属区域
+    pub enum ClassificationRegion("classification_region") {
+        Undeclared = 0,     // 未声明
+        EuropeanUnion = 1,  // 欧盟
+        China = 2,          // 中国
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Serialize, Deserialize};
+
+/// 整包的完整性校验算法，校验值以小端序写在子消息之后
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Integrity {
+    /// 不附加校验值
+    None,
+    /// CRC-16/XMODEM，仿大疆布局的默认算法
+    #[default]
+    Crc16Xmodem,
+    /// CRC-16/CCITT-FALSE
+    Crc16CcittFalse,
+    /// CRC-16/KERMIT
+    Crc16Kermit,
+    /// CRC-16/ARC
+    Crc16Arc,
+    /// CRC-32 (IEEE 802.3)
+    Crc32,
+}
+
+impl Integrity {
+    /// 校验值占用的字节数
+    pub fn checksum_length(self) -> usize {
+        match self {
+            Integrity::None => 0,
+            Integrity::Crc16Xmodem
+            | Integrity::Crc16CcittFalse
+            | Integrity::Crc16Kermit
+            | Integrity::Crc16Arc => 2,
+            Integrity::Crc32 => 4,
+        }
+    }
+
+    /// 计算校验值，`None` 时为 0
+    pub fn compute(self, data: &[u8]) -> u32 {
+        match self {
+            Integrity::None => 0,
+            Integrity::Crc16Xmodem => crc16::State::<crc16::XMODEM>::calculate(data) as u32,
+            Integrity::Crc16CcittFalse => crc16::State::<crc16::CCITT_FALSE>::calculate(data) as u32,
+            Integrity::Crc16Kermit => crc16::State::<crc16::KERMIT>::calculate(data) as u32,
+            Integrity::Crc16Arc => crc16::State::<crc16::ARC>::calculate(data) as u32,
+            Integrity::Crc32 => crc32fast::hash(data),
+        }
+    }
+
+    /// 按校验值长度以小端序写入
+    pub fn write(self, checksum: u32, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&checksum.to_le_bytes()[..self.checksum_length()]);
+    }
+
+    /// 按校验值长度以小端序读取，`data` 至少包含 `checksum_length()` 字节
+    pub fn read(self, data: &[u8]) -> u32 {
+        let mut checksum = [0u8; 4];
+        let length = self.checksum_length();
+        checksum[..length].copy_from_slice(&data[..length]);
+        u32::from_le_bytes(checksum)
+    }
+
+    /// 故障注入时写入的错误校验值，保证与正确值不同
+    pub fn corrupt(self, checksum: u32) -> u32 {
+        match self.checksum_length() {
+            2 => checksum ^ 0xFFFF,
+            _ => !checksum,
+        }
+    }
+}
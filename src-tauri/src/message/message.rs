@@ -1,20 +1,51 @@
 use std::fmt;
 use std::str;
 
+use super::describe::FieldNode;
+use super::protocol::WireFormat;
+
 // 公共消息错误类型
 #[derive(Debug, PartialEq)]
 pub enum MessageError {
     InsufficientLength(usize, usize),  // 期望长度, 实际长度
     InvalidUtf8(str::Utf8Error),        // UTF-8 格式错误
     UnknownMessageType(u8),             // 未知消息类型
+    DataTooLong(usize, usize),          // 最大长度, 实际长度
+    ChecksumMismatch(u32, u32),         // 期望校验和, 实际校验和
+    TooManyMessages(usize, usize),      // 整包最多消息数, 实际消息数
+    InvalidFieldValue(&'static str, u8), // 字段名, 未定义的取值
+    ValueOutOfRange(&'static str, f64), // 字段名, 超出范围的物理量
+    FieldOutOfRange {                   // 严格解码时字段取值不合规
+        field: &'static str,            // 字段名
+        offset: usize,                  // 字段所在字节偏移
+        value: i64,                     // 实际取值
+        allowed: &'static str,          // 允许的取值范围
+    },
+    ProtocolVersionMismatch(u8, u8),    // 期望协议版本, 实际协议版本
+    TrailingBytes(usize),               // 多余的字节数
+    InvalidUasId(String, &'static str), // UAS ID, 不合规的原因
+}
+
+impl MessageError {
+    /// 将字段偏移从消息内偏移换算为整帧偏移
+    pub fn at_offset(self, base: usize) -> Self {
+        match self {
+            MessageError::FieldOutOfRange { field, offset, value, allowed } =>
+                MessageError::FieldOutOfRange { field, offset: offset + base, value, allowed },
+            other => other,
+        }
+    }
 }
 
-// 公共消息类型，目前根据大疆，有3种
+// 公共消息类型，目前根据大疆，有3种，另加认证报文、自定义描述报文和运营人识别报文
 #[derive(Debug, PartialEq)]
 pub enum MessageType {
     BaseMessageType = 0,
     PositionVectorMessageType = 1,
+    AuthenticationMessageType = 2,
+    SelfIdMessageType = 3,
     SystemMessageType = 4,
+    OperatorIdMessageType = 5,
 }
 
 impl std::error::Error for MessageError {}
@@ -27,16 +58,50 @@ impl fmt::Display for MessageError {
                 write!(f, "文本格式错误: {}", e),
             MessageError::UnknownMessageType(t) => 
                 write!(f, "未知消息类型: 0x{:02X}", t),
+            MessageError::DataTooLong(max, actual) =>
+                write!(f, "数据过长: 最多 {} 字节, 实际 {} 字节", max, actual),
+            MessageError::ChecksumMismatch(expected, actual) =>
+                write!(f, "校验和错误: 期望 0x{:04X}, 实际 0x{:04X}", expected, actual),
+            MessageError::TooManyMessages(max, actual) =>
+                write!(f, "整包消息过多: 最多 {} 条, 实际 {} 条", max, actual),
+            MessageError::InvalidFieldValue(field, value) =>
+                write!(f, "字段取值无效: {} = {}", field, value),
+            MessageError::ValueOutOfRange(field, value) =>
+                write!(f, "物理量超出范围: {} = {}", field, value),
+            MessageError::FieldOutOfRange { field, offset, value, allowed } =>
+                write!(f, "字段超出范围: {} (偏移 {}) = {}, 允许 {}", field, offset, value, allowed),
+            MessageError::ProtocolVersionMismatch(expected, actual) =>
+                write!(f, "协议版本不匹配: 期望 0x{:X}, 实际 0x{:X}", expected, actual),
+            MessageError::TrailingBytes(count) =>
+                write!(f, "存在多余数据: {} 字节", count),
+            MessageError::InvalidUasId(uas_id, reason) =>
+                write!(f, "UAS ID 格式错误: '{}' ({})", uas_id, reason),
         }
     }
 }
 
 /// 所有消息类型必须实现的 trait
 pub trait Message {
-    /// 从字节数组解析消息
-    fn from_bytes(data: &[u8]) -> Result<Self, MessageError> where Self: Sized;
-    // 从结构体到字节的编码
-    fn encode(&self) -> Vec<u8> ;
-    /// 打印消息内容
-    fn print(&self);
+    /// 从字节数组解析消息，按默认线上格式
+    fn from_bytes(data: &[u8]) -> Result<Self, MessageError> where Self: Sized {
+        Self::from_bytes_with(data, &WireFormat::default())
+    }
+    /// 按指定线上格式从字节数组解析消息
+    fn from_bytes_with(data: &[u8], format: &WireFormat) -> Result<Self, MessageError> where Self: Sized;
+    // 从结构体到字节的编码，按默认线上格式
+    fn encode(&self) -> Result<Vec<u8>, MessageError> {
+        self.encode_with(&WireFormat::default())
+    }
+    /// 按指定线上格式编码，字段无法写入线上格式时返回错误
+    fn encode_with(&self, format: &WireFormat) -> Result<Vec<u8>, MessageError>;
+    /// 严格模式下校验各字段取值，错误中的偏移以类型/协议字节为 0
+    fn validate(&self) -> Result<(), MessageError> {
+        Ok(())
+    }
+    /// 字段描述树，包含解码后的取值和线上的原始取值，用于日志、JSON 导出和界面展示
+    fn describe(&self) -> FieldNode;
+    /// 按指定线上格式给出字段描述树，原始取值随线上格式变化的报文需要覆盖
+    fn describe_with(&self, _format: &WireFormat) -> FieldNode {
+        self.describe()
+    }
 }
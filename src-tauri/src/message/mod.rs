@@ -1,48 +1,215 @@
 
 pub mod message;
+pub mod protocol;
+pub mod describe;
+pub mod integrity;
+pub mod field_types;
+mod bitfield;
+mod units;
+pub mod timestamp;
+pub mod uas_id;
 pub mod base_message;
 pub mod position_vector_message;
 pub mod system_message;
+pub mod self_id_message;
+pub mod operator_id_message;
 pub mod packet_message;
-use tracing::info;
+pub mod authentication_message;
+use std::fmt;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
 
 use crate::message::message::Message;
+use crate::message::protocol::{EncodingProfile, ProtocolVersion, WireFormat};
 
+/// 任意类型的单条消息，序列化时以 `type` 字段标记具体类型
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum AnyMessage {
     Base(base_message::BaseMessage),
     PositionVector(position_vector_message::PositionVectorMessage),
-    System(system_message::SystemMessage)
+    Authentication(authentication_message::AuthenticationMessage),
+    System(system_message::SystemMessage),
+    SelfId(self_id_message::SelfIdMessage),
+    OperatorId(operator_id_message::OperatorIdMessage),
 }
 
 impl AnyMessage {
     /// 工厂方法 - 根据首字节的消息类型创建具体的消息实例
+    ///
+    /// 字段布局按首字节低4位的协议版本选择，未知版本按默认版本解析
     pub fn from_bytes(data: &[u8]) -> Result<Self, message::MessageError> {
+        if data.is_empty() {
+            return Err(message::MessageError::InsufficientLength(1, 0));
+        }
+        let version = ProtocolVersion::from_nibble(data[0] & 0x0f, EncodingProfile::DjiCompat).unwrap_or_default();
+        Self::from_bytes_with(data, &WireFormat::new(version))
+    }
+
+    /// 按指定线上格式解析，忽略首字节中的协议版本
+    pub fn from_bytes_with(data: &[u8], format: &WireFormat) -> Result<Self, message::MessageError> {
 
         if data.is_empty() {
             return Err(message::MessageError::InsufficientLength(1, 0));
         }
         let message_type = (data[0] >> 4) & 0x0f;
         let content = &data[1..];
-        info!("message type = {}", message_type);
         match message_type {
             base_message::BaseMessage::MESSAGE_TYPE => {
-                base_message::BaseMessage::from_bytes(content).map(AnyMessage::Base)
+                base_message::BaseMessage::from_bytes_with(content, format).map(AnyMessage::Base)
             },
             position_vector_message::PositionVectorMessage::MESSAGE_TYPE => {
-                position_vector_message::PositionVectorMessage::from_bytes(content).map(AnyMessage::PositionVector)
+                position_vector_message::PositionVectorMessage::from_bytes_with(content, format).map(AnyMessage::PositionVector)
+            },
+            authentication_message::AuthenticationMessage::MESSAGE_TYPE => {
+                authentication_message::AuthenticationMessage::from_bytes_with(content, format).map(AnyMessage::Authentication)
             },
             system_message::SystemMessage::MESSAGE_TYPE => {
-                system_message::SystemMessage::from_bytes(content).map(AnyMessage::System)
+                system_message::SystemMessage::from_bytes_with(content, format).map(AnyMessage::System)
+            },
+            self_id_message::SelfIdMessage::MESSAGE_TYPE => {
+                self_id_message::SelfIdMessage::from_bytes_with(content, format).map(AnyMessage::SelfId)
+            },
+            operator_id_message::OperatorIdMessage::MESSAGE_TYPE => {
+                operator_id_message::OperatorIdMessage::from_bytes_with(content, format).map(AnyMessage::OperatorId)
             },
             t => Err(message::MessageError::UnknownMessageType(t)),
         }
     }
     
-    pub fn print(&self) {
+    // 单条消息的长度 (类型/协议字节 + 24字节内容)
+    const MESSAGE_LENGTH: usize = 25;
+
+    /// 严格模式解码：要求长度恰好为 25 字节、协议版本为已知版本，并校验各字段取值
+    pub fn from_bytes_strict(data: &[u8]) -> Result<Self, message::MessageError> {
+        Self::from_bytes_strict_with(data, &WireFormat::default())
+    }
+
+    /// 按指定编码风格严格解码，协议版本取自首字节
+    pub fn from_bytes_strict_with(data: &[u8], format: &WireFormat) -> Result<Self, message::MessageError> {
+        if data.len() < Self::MESSAGE_LENGTH {
+            return Err(message::MessageError::InsufficientLength(Self::MESSAGE_LENGTH, data.len()));
+        }
+        if data.len() > Self::MESSAGE_LENGTH {
+            return Err(message::MessageError::TrailingBytes(data.len() - Self::MESSAGE_LENGTH));
+        }
+        // 未知的协议版本按版本不一致报告，期望值为调用方给出的版本
+        let mut format = *format;
+        format.version = ProtocolVersion::from_nibble(data[0] & 0x0f, format.profile)
+            .map_err(|_| message::MessageError::ProtocolVersionMismatch(format.version.nibble(), data[0] & 0x0f))?;
+
+        let message = Self::from_bytes_with(data, &format)?;
+        message.validate()?;
+        Ok(message)
+    }
+
+    /// 校验各字段取值，错误中的偏移以类型/协议字节为 0
+    pub fn validate(&self) -> Result<(), message::MessageError> {
         match self {
-            AnyMessage::Base(msg) => msg.print(),
-            AnyMessage::PositionVector(msg) => msg.print(),
-            AnyMessage::System(msg) => msg.print(),
+            AnyMessage::Base(msg) => msg.validate(),
+            AnyMessage::PositionVector(msg) => msg.validate(),
+            AnyMessage::Authentication(msg) => msg.validate(),
+            AnyMessage::System(msg) => msg.validate(),
+            AnyMessage::SelfId(msg) => msg.validate(),
+            AnyMessage::OperatorId(msg) => msg.validate(),
         }
     }
-}
\ No newline at end of file
+
+    /// 按具体消息类型编码，结果包含首字节的类型/协议
+    pub fn encode(&self) -> Result<Vec<u8>, message::MessageError> {
+        self.encode_with(&WireFormat::default())
+    }
+
+    /// 按指定线上格式编码
+    pub fn encode_with(&self, format: &WireFormat) -> Result<Vec<u8>, message::MessageError> {
+        match self {
+            AnyMessage::Base(msg) => msg.encode_with(format),
+            AnyMessage::PositionVector(msg) => msg.encode_with(format),
+            AnyMessage::Authentication(msg) => msg.encode_with(format),
+            AnyMessage::System(msg) => msg.encode_with(format),
+            AnyMessage::SelfId(msg) => msg.encode_with(format),
+            AnyMessage::OperatorId(msg) => msg.encode_with(format),
+        }
+    }
+
+    /// 按时间戳策略给出的时间更新位置向量报文和系统报文的时间戳
+    pub fn apply_timestamp(&mut self, time: DateTime<Utc>) {
+        match self {
+            AnyMessage::PositionVector(msg) => msg.set_timestamp_utc(time),
+            AnyMessage::System(msg) => msg.set_timestamp_utc(time),
+            _ => {}
+        }
+    }
+
+    /// 按具体消息类型给出字段描述树
+    pub fn describe(&self) -> describe::FieldNode {
+        match self {
+            AnyMessage::Base(msg) => msg.describe(),
+            AnyMessage::PositionVector(msg) => msg.describe(),
+            AnyMessage::Authentication(msg) => msg.describe(),
+            AnyMessage::System(msg) => msg.describe(),
+            AnyMessage::SelfId(msg) => msg.describe(),
+            AnyMessage::OperatorId(msg) => msg.describe(),
+        }
+    }
+
+    /// 按指定线上格式给出字段描述树，原始取值与该格式下编码的字节一致
+    pub fn describe_with(&self, format: &WireFormat) -> describe::FieldNode {
+        match self {
+            AnyMessage::Base(msg) => msg.describe_with(format),
+            AnyMessage::PositionVector(msg) => msg.describe_with(format),
+            AnyMessage::Authentication(msg) => msg.describe_with(format),
+            AnyMessage::System(msg) => msg.describe_with(format),
+            AnyMessage::SelfId(msg) => msg.describe_with(format),
+            AnyMessage::OperatorId(msg) => msg.describe_with(format),
+        }
+    }
+}
+
+impl fmt::Display for AnyMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnyMessage::Base(msg) => write!(f,
+                "基本报文: ID类型={:?}, UA类型={:?}, UAS ID='{}'",
+                msg.id_type, msg.ua_type, msg.uas_id),
+            AnyMessage::PositionVector(msg) => write!(f,
+                "位置向量报文: 运行状态={:?}, 位置=({:.7}, {:.7}), 几何高度={}m, 航迹角={}°, 地速={:.2}m/s, 时间戳={}",
+                msg.run_status, msg.latitude_deg(), msg.longitude_deg(),
+                msg.geometric_altitude_m(), msg.track_deg(), msg.ground_speed_mps(), msg.timestamp),
+            AnyMessage::Authentication(msg) => write!(f,
+                "认证报文: 认证类型={}, 页索引={}, 数据={}",
+                msg.auth_type, msg.page_number, hex::encode(&msg.auth_data)),
+            AnyMessage::System(msg) => write!(f,
+                "系统报文: 分类区域={:?}, 控制站位置=({:.7}, {:.7}), 控制站类型={:?}, 时间戳={}",
+                msg.classification_region, msg.operator_latitude_deg(), msg.operator_longitude_deg(),
+                msg.station_type, msg.timestamp),
+            AnyMessage::SelfId(msg) => write!(f,
+                "自定义描述报文: 描述类型={}, 描述='{}'",
+                msg.description_type, msg.description),
+            AnyMessage::OperatorId(msg) => write!(f,
+                "运营人识别报文: ID类型={}, 运营人ID='{}'",
+                msg.operator_id_type, msg.operator_id),
+        }
+    }
+}
+
+macro_rules! impl_from_message {
+    ($($variant:ident($message:ty)),* $(,)?) => {
+        $(
+            impl From<$message> for AnyMessage {
+                fn from(message: $message) -> Self {
+                    AnyMessage::$variant(message)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_message!(
+    Base(base_message::BaseMessage),
+    PositionVector(position_vector_message::PositionVectorMessage),
+    Authentication(authentication_message::AuthenticationMessage),
+    System(system_message::SystemMessage),
+    SelfId(self_id_message::SelfIdMessage),
+    OperatorId(operator_id_message::OperatorIdMessage),
+);
@@ -0,0 +1,103 @@
+use std::str;
+use tracing::info;
+use serde::{Serialize, Deserialize};
+
+use crate::message::message::MessageType;
+
+use super::describe::FieldNode;
+use super::message::{Message, MessageError};
+use super::protocol::WireFormat;
+
+/// 运营人识别报文（报文类型 0x5），用于广播运营人登记注册号
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorIdMessage {
+    pub operator_id_type: u8, // 运营人ID类型 (0: 运营人登记号)
+    pub operator_id: String,  // 运营人ID (最多20字节)
+    #[serde(default)]
+    pub reserved: [u8; 3],    // 3 字节预留空间
+}
+
+impl OperatorIdMessage {
+    pub const MESSAGE_TYPE: u8 = 0x05;
+    const EXPECTED_LENGTH: usize = 24;
+    const OPERATOR_ID_LENGTH: usize = 20;
+}
+
+impl Message for OperatorIdMessage {
+    /// 从 u8 数组解析为结构化数据
+    ///
+    /// # 参数
+    /// - `data`: 至少包含 24 字节的输入数据
+    ///
+    /// # 错误
+    /// - 当输入数据长度不足时返回 ParseError::InsufficientLength
+    /// - 当运营人ID不是有效的 UTF-8 时返回 ParseError::InvalidUtf8
+    fn from_bytes_with(data: &[u8], _format: &WireFormat) -> Result<Self, MessageError> {
+        if data.len() < Self::EXPECTED_LENGTH {
+            return Err(MessageError::InsufficientLength(
+                Self::EXPECTED_LENGTH,
+                data.len()
+            ));
+        }
+
+        // 解析运营人ID类型 (起始字节 1)
+        let operator_id_type = data[0];
+
+        // 解析运营人ID (起始字节 2，长度 20)
+        let id_end = 1 + Self::OPERATOR_ID_LENGTH;
+        let operator_id = match str::from_utf8(&data[1..id_end]) {
+            Ok(s) => s.trim_end_matches('\0').trim_end().to_string(),
+            Err(e) => {
+                info!("operator id message utf8 error.");
+                return Err(MessageError::InvalidUtf8(e))
+            }
+        };
+
+        // 解析预留字段 (起始字节 22)
+        let reserved = [data[id_end], data[id_end + 1], data[id_end + 2]];
+
+        Ok(Self {
+            operator_id_type,
+            operator_id,
+            reserved,
+        })
+    }
+
+    fn encode_with(&self, format: &WireFormat) -> Result<Vec<u8>, MessageError> {
+        let mut bytes: Vec<u8> = Vec::new();
+
+        let message_protocol = format.header(MessageType::OperatorIdMessageType);
+        bytes.push(message_protocol);
+        bytes.push(self.operator_id_type);
+
+        // 编码运营人ID（最多20字节，超出部分按字符边界截断）
+        let mut operator_id = [0u8; Self::OPERATOR_ID_LENGTH];
+        let mut len = self.operator_id.len().min(Self::OPERATOR_ID_LENGTH);
+        while !self.operator_id.is_char_boundary(len) {
+            len -= 1;
+        }
+        operator_id[..len].copy_from_slice(&self.operator_id.as_bytes()[..len]);
+        bytes.extend_from_slice(&operator_id);
+
+        // 预留字段
+        bytes.extend_from_slice(&self.reserved);
+
+        Ok(bytes)
+    }
+
+    fn validate(&self) -> Result<(), MessageError> {
+        match self.operator_id_type {
+            0 | 201..=255 => Ok(()),
+            value => Err(MessageError::FieldOutOfRange {
+                field: "operator_id_type", offset: 1, value: value as i64, allowed: "0, 201-255",
+            }),
+        }
+    }
+
+    fn describe(&self) -> FieldNode {
+        FieldNode::group("operator_id_message", "运营人识别报文")
+            .child(FieldNode::field("operator_id_type", "运营人ID类型", self.operator_id_type))
+            .child(FieldNode::field("operator_id", "运营人ID", &self.operator_id).raw_bytes(self.operator_id.as_bytes()))
+            .child(FieldNode::group("reserved", "预留字段").raw_bytes(&self.reserved))
+    }
+}
@@ -1,133 +1,925 @@
-use crate::message::{base_message::BaseMessage, position_vector_message::PositionVectorMessage, system_message::SystemMessage};
+use crate::message::{AnyMessage, authentication_message::AuthenticationMessage, base_message::BaseMessage, position_vector_message::PositionVectorMessage, self_id_message::SelfIdMessage, operator_id_message::OperatorIdMessage, system_message::SystemMessage};
 use super::message::{Message, MessageError};
-use serde::{Serialize, Deserialize};
+use super::describe::FieldNode;
+use super::integrity::Integrity;
+use super::protocol::{EncodingProfile, ProtocolVersion, WireFormat};
+use super::timestamp::TimestampPolicy;
+use super::uas_id;
+use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU8, Ordering};
 
 static RID_COUNTER: AtomicU8 = AtomicU8::new(1);
 
-/// 以整包形式发送，其中包含了BaseMessage， SystemMessage, PositionVectorMessage，主要模仿收到大疆的结构类型
-#[derive(Debug, Serialize, Deserialize)]
+/// 整包报文（报文类型 0xF），可携带任意组合的子消息，主要模仿收到大疆的结构类型
+///
+/// 线上格式（所有多字节字段均为小端序）:
+///
+/// | 偏移 | 长度 | 字段 |
+/// |------|------|------|
+/// | 0 | 1 | 消息计数器 |
+/// | 1 | 1 | 协议版本 (高4位 0xF 为整包类型, 低4位为版本) |
+/// | 2 | 1 | 每帧大小 (25) |
+/// | 3 | 1 | 帧数量 (最多9帧) |
+/// | 4 | 25 × 帧数量 | 子消息，按加入顺序排列，每帧首字节为类型/协议 |
+/// | .. | 0/2/4 | 校验值，覆盖包头和全部子消息，算法由 `Integrity` 决定 (默认 CRC16 XMODEM) |
+/// | .. | 3 | 预留 |
+///
+/// `EncodingProfile::AstmF3411` 下省略预留字段且默认不校验，计数器加整包即为标准
+/// Wi-Fi 信标厂商 IE 中的内容。
+///
+/// 设置 `corrupt_checksum` 后编码时故意写入错误的校验值，用于验证接收端能否丢弃损坏的帧。
+///
+/// 子消息的字段布局和类型/协议字节的低4位由整包的 `WireFormat` 决定，解码时以包头中的协议版本为准。
+///
+/// 位置向量报文和系统报文的时间戳在编码时按 `TimestampPolicy` 取值，解码得到的整包使用
+/// `TimestampPolicy::Provided`，重新编码时逐字节一致。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "PacketMessageJson", into = "PacketMessageJson")]
 pub struct PacketMessage {
-    protocol_version: u8,          // 协议版本（1字节）
-    message_counter: u8,          // 消息计数器（2字节）
-    message_size: u8,             // 消息总大小（2字节）
-    message_quantity: u8,          // 包含消息数量（1字节）
-    base_message: BaseMessage,
-    system_message: SystemMessage,
-    position_message: PositionVectorMessage,
-    checksum: u16,                 // CRC16校验和（2字节）
+    format: WireFormat,            // 线上格式，协议版本写入包头低4位
+    message_counter: u8,          // 消息计数器（1字节）
+    messages: Vec<AnyMessage>,     // 子消息，帧大小和数量由此计算
+    checksum: u32,                 // 解码时读到的校验值
     reserved: [u8; 3],             // 3字节预留
+    timestamp_policy: TimestampPolicy, // 编码时的时间戳策略
+    corrupt_checksum: bool,        // 故障注入：编码时写入错误的校验值
 }
 
 impl PacketMessage {
     // 每一帧的大小
     const MESSAGE_SIZE:u8 = 25;
-    // 每包一共3帧
-    const MESSAGE_QUANTITY:u8 = 3;
-    pub fn new(
-        base: BaseMessage,
-        system: SystemMessage,
-        position: PositionVectorMessage
-    ) -> Self {
+    // 标准规定每包最多9帧
+    pub const MAX_MESSAGES: usize = 9;
+    // 包头：计数器、协议版本、帧大小、帧数量
+    const HEADER_LENGTH: usize = 4;
+    // 包尾中的预留字段长度，ASTM 格式无预留字段
+    const RESERVED_LENGTH: usize = 3;
+    // 整包的报文类型 (协议版本字节的高4位)
+    const PACK_MESSAGE_TYPE: u8 = 0x0f;
+
+    pub fn builder() -> PacketMessageBuilder {
+        PacketMessageBuilder::default()
+    }
+
+    /// 包内的子消息
+    pub fn messages(&self) -> &[AnyMessage] {
+        &self.messages
+    }
+
+    /// 修改包内指定位置的子消息，帧数量保持不变
+    pub fn message_mut(&mut self, index: usize) -> Option<&mut AnyMessage> {
+        self.messages.get_mut(index)
+    }
+
+    // 获取rid加前缀为ssid，仿大疆，使用包内第一条基本报文的UAS ID
+    pub fn get_ssid(&self) -> String {
+        let uas_id = self.messages.iter().find_map(|message| match message {
+            AnyMessage::Base(base) => Some(base.uas_id.as_str()),
+            _ => None,
+        });
+        format!("RID-{}", uas_id.unwrap_or_default())
+    }
+
+    /// 从全局计数器取下一个消息计数，每次广播前调用
+    pub fn assign_next_counter(&mut self) {
+        self.message_counter = RID_COUNTER.fetch_add(0x01, Ordering::SeqCst);
+    }
+
+    /// 严格模式解码：在常规解码基础上校验协议版本、帧大小、帧数量、多余字节，
+    /// 以及每条子消息的字段取值，错误中的偏移为整包内的字节偏移
+    pub fn from_bytes_strict(data: &[u8]) -> Result<Self, MessageError> {
+        Self::from_bytes_strict_with(data, &WireFormat::default())
+    }
+
+    /// 按指定编码风格严格解码，协议版本取自包头
+    pub fn from_bytes_strict_with(data: &[u8], format: &WireFormat) -> Result<Self, MessageError> {
+        if data.len() < Self::HEADER_LENGTH {
+            return Err(MessageError::InsufficientLength(Self::HEADER_LENGTH, data.len()));
+        }
+
+        let pack_type = (data[1] >> 4) & 0x0f;
+        if pack_type != Self::PACK_MESSAGE_TYPE {
+            return Err(MessageError::UnknownMessageType(pack_type));
+        }
+        // 未知的协议版本按版本不一致报告，期望值为调用方给出的版本
+        let version = ProtocolVersion::from_nibble(data[1] & 0x0f, format.profile)
+            .map_err(|_| MessageError::ProtocolVersionMismatch(format.version.nibble(), data[1] & 0x0f))?;
+        if data[2] != Self::MESSAGE_SIZE {
+            return Err(MessageError::FieldOutOfRange {
+                field: "message_size", offset: 2, value: data[2] as i64, allowed: "25",
+            });
+        }
+        if data[3] as usize > Self::MAX_MESSAGES {
+            return Err(MessageError::FieldOutOfRange {
+                field: "message_quantity", offset: 3, value: data[3] as i64, allowed: "0-9",
+            });
+        }
+
+        let body_end = Self::HEADER_LENGTH + Self::MESSAGE_SIZE as usize * data[3] as usize;
+        let expected_length = body_end + Self::trailer_length(format);
+        if data.len() > expected_length {
+            return Err(MessageError::TrailingBytes(data.len() - expected_length));
+        }
+
+        let packet = Self::from_bytes_with(data, format)?;
+        let frame_format = packet.format;
+        for (index, frame) in data[Self::HEADER_LENGTH..body_end].chunks(Self::MESSAGE_SIZE as usize).enumerate() {
+            let frame_offset = Self::HEADER_LENGTH + index * Self::MESSAGE_SIZE as usize;
+            let frame_version = frame[0] & 0x0f;
+            if frame_version != version.nibble() {
+                return Err(MessageError::ProtocolVersionMismatch(version.nibble(), frame_version));
+            }
+            AnyMessage::from_bytes_strict_with(frame, &frame_format).map_err(|e| e.at_offset(frame_offset))?;
+        }
+        Ok(packet)
+    }
+
+    /// 整包及子消息使用的线上格式
+    pub fn wire_format(&self) -> WireFormat {
+        self.format
+    }
+
+    // 包尾：校验值 + 预留字段
+    fn trailer_length(format: &WireFormat) -> usize {
+        let reserved = if format.is_astm() { 0 } else { Self::RESERVED_LENGTH };
+        format.integrity().checksum_length() + reserved
+    }
+
+    /// 故障注入：编码时写入错误的校验值，校验算法为 `Integrity::None` 时无效
+    pub fn set_corrupt_checksum(&mut self, corrupt: bool) {
+        self.corrupt_checksum = corrupt;
+    }
+
+    pub fn set_timestamp_policy(&mut self, policy: TimestampPolicy) {
+        self.timestamp_policy = policy;
+    }
+
+    /// 解码时读到的校验值
+    pub fn checksum(&self) -> u32 {
+        self.checksum
+    }
+}
+
+/// 整包构造器，帧大小和帧数量在构造时自动计算
+#[derive(Debug)]
+pub struct PacketMessageBuilder {
+    format: WireFormat,
+    message_counter: u8,
+    messages: Vec<AnyMessage>,
+    reserved: [u8; 3],
+    timestamp_policy: TimestampPolicy,
+    corrupt_checksum: bool,
+}
+
+impl Default for PacketMessageBuilder {
+    fn default() -> Self {
         Self {
-            protocol_version: 0xf1,
+            format: WireFormat::default(),
             message_counter: 1,
-            message_size: Self::MESSAGE_SIZE,
-            message_quantity: Self::MESSAGE_QUANTITY,
-            base_message: base,
-            system_message: system,
-            position_message: position,
-            checksum: 0,
+            messages: Vec::new(),
             reserved: [0; 3],
+            timestamp_policy: TimestampPolicy::default(),
+            corrupt_checksum: false,
         }
     }
-    // 获取rid加前缀为ssid，仿大疆
-    pub fn get_ssid(&self) -> String {
-        return format!("RID-{}", self.base_message.uas_id.clone());
+}
+
+impl PacketMessageBuilder {
+    /// 协议版本，默认 GB 42590
+    pub fn protocol_version(mut self, version: ProtocolVersion) -> Self {
+        self.format.version = version;
+        self
+    }
+
+    /// 编码风格，默认仿大疆布局
+    pub fn profile(mut self, profile: EncodingProfile) -> Self {
+        self.format.profile = profile;
+        self
+    }
+
+    /// 整包校验算法，默认按编码风格选择
+    pub fn integrity(mut self, integrity: Integrity) -> Self {
+        self.format.integrity = Some(integrity);
+        self
+    }
+
+    /// 故障注入：编码时写入错误的校验值
+    pub fn corrupt_checksum(mut self, corrupt: bool) -> Self {
+        self.corrupt_checksum = corrupt;
+        self
+    }
+
+    pub fn wire_format(mut self, format: WireFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn message_counter(mut self, message_counter: u8) -> Self {
+        self.message_counter = message_counter;
+        self
+    }
+
+    pub fn reserved(mut self, reserved: [u8; 3]) -> Self {
+        self.reserved = reserved;
+        self
+    }
+
+    /// 编码时的时间戳策略，默认使用系统当前时间
+    pub fn timestamp_policy(mut self, policy: TimestampPolicy) -> Self {
+        self.timestamp_policy = policy;
+        self
+    }
+
+    /// 追加一条子消息，按追加顺序编码
+    pub fn message(mut self, message: impl Into<AnyMessage>) -> Self {
+        self.messages.push(message.into());
+        self
+    }
+
+    /// 追加多条子消息，按迭代顺序编码
+    pub fn messages<I>(mut self, messages: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<AnyMessage>,
+    {
+        self.messages.extend(messages.into_iter().map(Into::into));
+        self
+    }
+
+    /// # 错误
+    /// 子消息超过 9 条时返回 MessageError::TooManyMessages
+    pub fn build(mut self) -> Result<PacketMessage, MessageError> {
+        if self.messages.len() > PacketMessage::MAX_MESSAGES {
+            return Err(MessageError::TooManyMessages(PacketMessage::MAX_MESSAGES, self.messages.len()));
+        }
+
+        let messages = std::mem::take(&mut self.messages);
+        Ok(self.pack(messages))
+    }
+
+    /// 认证页较多、一包放不下时拆分为连续多包：第一包携带其他全部子消息和尽量多的认证页，
+    /// 后续每包携带全部基本报文 (接收端据此关联到同一架无人机) 和剩余的认证页
+    ///
+    /// # 错误
+    /// 认证页以外的子消息超过 9 条，或基本报文占满整包时返回 MessageError::TooManyMessages
+    pub fn build_sequence(self) -> Result<Vec<PacketMessage>, MessageError> {
+        if self.messages.len() <= PacketMessage::MAX_MESSAGES {
+            return Ok(vec![self.build()?]);
+        }
+
+        let (pages, others): (Vec<AnyMessage>, Vec<AnyMessage>) = self.messages
+            .iter()
+            .cloned()
+            .partition(|message| matches!(message, AnyMessage::Authentication(_)));
+        if others.len() > PacketMessage::MAX_MESSAGES {
+            return Err(MessageError::TooManyMessages(PacketMessage::MAX_MESSAGES, others.len()));
+        }
+        let bases: Vec<AnyMessage> = others
+            .iter()
+            .filter(|message| matches!(message, AnyMessage::Base(_)))
+            .cloned()
+            .collect();
+        let continuation_capacity = PacketMessage::MAX_MESSAGES - bases.len();
+        if continuation_capacity == 0 {
+            return Err(MessageError::TooManyMessages(PacketMessage::MAX_MESSAGES, bases.len() + 1));
+        }
+
+        let first_capacity = PacketMessage::MAX_MESSAGES - others.len();
+        let mut pages = pages.into_iter();
+        let mut packs = vec![self.pack(others.into_iter().chain(pages.by_ref().take(first_capacity)).collect())];
+        loop {
+            let chunk: Vec<AnyMessage> = pages.by_ref().take(continuation_capacity).collect();
+            if chunk.is_empty() {
+                break;
+            }
+            packs.push(self.pack(bases.iter().cloned().chain(chunk).collect()));
+        }
+        Ok(packs)
+    }
+
+    fn pack(&self, messages: Vec<AnyMessage>) -> PacketMessage {
+        PacketMessage {
+            format: self.format,
+            message_counter: self.message_counter,
+            messages,
+            checksum: 0,
+            reserved: self.reserved,
+            timestamp_policy: self.timestamp_policy.clone(),
+            corrupt_checksum: self.corrupt_checksum,
+        }
+    }
+}
+
+/// 一条 MQTT 消息对应的连续整包，认证页一包放不下时多于一包，按顺序发送
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "PacketMessageJson")]
+pub struct PacketSequence(Vec<PacketMessage>);
+
+impl PacketSequence {
+    pub fn packs(&self) -> &[PacketMessage] {
+        &self.0
+    }
+
+    pub fn into_packs(self) -> Vec<PacketMessage> {
+        self.0
+    }
+}
+
+/// MQTT 下发的 JSON 格式
+///
+/// 子消息放在 `messages` 列表中，按列表顺序编码，每项以 `type` 区分报文类型。
+/// 旧格式的固定字段 (`base_message`、`position_message`、`system_message` 等) 仍可使用，
+/// 按原来的顺序排在 `messages` 之前，认证页按页索引排序后排在最后。
+#[derive(Debug, Serialize, Deserialize)]
+struct PacketMessageJson {
+    #[serde(default = "PacketMessageJson::default_protocol_version")]
+    protocol_version: u8,
+    #[serde(default)]
+    encoding_profile: EncodingProfile,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    integrity: Option<Integrity>,
+    #[serde(default)]
+    corrupt_checksum: bool,
+    #[serde(default)]
+    message_counter: u8,
+    #[serde(default)]
+    messages: Vec<AnyMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base_message: Option<BaseMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    position_message: Option<PositionVectorMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    system_message: Option<SystemMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    self_id_message: Option<SelfIdMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    operator_id_message: Option<OperatorIdMessage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    authentication_messages: Vec<AuthenticationMessage>,
+    #[serde(default)]
+    reserved: [u8; 3],
+    #[serde(default)]
+    timestamp_policy: TimestampPolicy,
+}
+
+impl PacketMessageJson {
+    fn default_protocol_version() -> u8 {
+        0xf1
+    }
+
+    fn into_builder(self) -> Result<PacketMessageBuilder, MessageError> {
+        let mut messages: Vec<AnyMessage> = Vec::new();
+        messages.extend(self.base_message.map(AnyMessage::from));
+        messages.extend(self.position_message.map(AnyMessage::from));
+        messages.extend(self.system_message.map(AnyMessage::from));
+        messages.extend(self.self_id_message.map(AnyMessage::from));
+        messages.extend(self.operator_id_message.map(AnyMessage::from));
+        messages.extend(self.messages);
+        let mut authentication = self.authentication_messages;
+        authentication.sort_by_key(|page| page.page_number);
+        messages.extend(authentication.into_iter().map(AnyMessage::from));
+
+        // 入口处按 ID 类型校验 UAS ID，不合规的 ID 不再下发
+        for message in &messages {
+            if let AnyMessage::Base(base) = message {
+                uas_id::validate_uas_id(base.id_type, &base.uas_id)?;
+            }
+        }
+
+        // 兼容整字节写法 (如 0xf1)，只取低4位的协议版本
+        let version = ProtocolVersion::from_nibble(self.protocol_version & 0x0f, self.encoding_profile)?;
+
+        let mut builder = PacketMessage::builder()
+            .protocol_version(version)
+            .profile(self.encoding_profile)
+            .corrupt_checksum(self.corrupt_checksum)
+            .message_counter(self.message_counter)
+            .messages(messages)
+            .reserved(self.reserved)
+            .timestamp_policy(self.timestamp_policy);
+        if let Some(integrity) = self.integrity {
+            builder = builder.integrity(integrity);
+        }
+        Ok(builder)
+    }
+}
+
+impl From<PacketMessage> for PacketMessageJson {
+    fn from(packet: PacketMessage) -> Self {
+        Self {
+            protocol_version: (PacketMessage::PACK_MESSAGE_TYPE << 4) | packet.format.version.nibble(),
+            encoding_profile: packet.format.profile,
+            integrity: packet.format.integrity,
+            corrupt_checksum: packet.corrupt_checksum,
+            message_counter: packet.message_counter,
+            messages: packet.messages,
+            base_message: None,
+            position_message: None,
+            system_message: None,
+            self_id_message: None,
+            operator_id_message: None,
+            authentication_messages: Vec::new(),
+            reserved: packet.reserved,
+            timestamp_policy: packet.timestamp_policy,
+        }
+    }
+}
+
+impl TryFrom<PacketMessageJson> for PacketMessage {
+    type Error = MessageError;
+
+    fn try_from(json: PacketMessageJson) -> Result<Self, Self::Error> {
+        json.into_builder()?.build()
+    }
+}
+
+impl TryFrom<PacketMessageJson> for PacketSequence {
+    type Error = MessageError;
+
+    fn try_from(json: PacketMessageJson) -> Result<Self, Self::Error> {
+        json.into_builder()?.build_sequence().map(PacketSequence)
     }
 }
 
 impl Message for PacketMessage {
-    fn from_bytes(data: &[u8]) -> Result<Self, MessageError> {
-        if data.len() < 16 {
-            return Err(MessageError::InsufficientLength(16, data.len()));
+    /// 协议版本取自包头，未知版本时使用 `format` 中的版本；编码风格取自 `format`
+    fn from_bytes_with(data: &[u8], format: &WireFormat) -> Result<Self, MessageError> {
+        if data.len() < Self::HEADER_LENGTH {
+            return Err(MessageError::InsufficientLength(Self::HEADER_LENGTH, data.len()));
         }
 
         // 解析头部
-        let message_counter = data[1];
-        let protocol_version = data[2];
-        let message_size = data[3];
-        let message_quantity = data[4];
-        
-        // 解析消息体
-        let base = BaseMessage::from_bytes(&data[5..29])?;
-        let system = SystemMessage::from_bytes(&data[29..61])?;
-        let position = PositionVectorMessage::from_bytes(&data[61..85])?;
-        
+        let message_counter = data[0];
+        let protocol_version = data[1];
+        let message_size = data[2];
+        let message_quantity = data[3];
+
+        let pack_type = (protocol_version >> 4) & 0x0f;
+        if pack_type != Self::PACK_MESSAGE_TYPE {
+            return Err(MessageError::UnknownMessageType(pack_type));
+        }
+
+        let mut format = *format;
+        format.version = ProtocolVersion::from_nibble(protocol_version & 0x0f, format.profile).unwrap_or(format.version);
+
+        // 每帧至少包含类型字节和24字节内容
+        if message_quantity > 0 && message_size < Self::MESSAGE_SIZE {
+            return Err(MessageError::InsufficientLength(Self::MESSAGE_SIZE as usize, message_size as usize));
+        }
+
+        let body_end = Self::HEADER_LENGTH + message_size as usize * message_quantity as usize;
+        let expected_length = body_end + Self::trailer_length(&format);
+        if data.len() < expected_length {
+            return Err(MessageError::InsufficientLength(expected_length, data.len()));
+        }
+
+        // 校验值覆盖包头和全部子消息
+        let integrity = format.integrity();
+        let expected_checksum = integrity.compute(&data[..body_end]);
+        let checksum = integrity.read(&data[body_end..]);
+        if checksum != expected_checksum {
+            return Err(MessageError::ChecksumMismatch(expected_checksum, checksum));
+        }
+
+        // 解析消息体，按每帧首字节的类型分派；帧数量为0时帧大小可能为0，不能按其分块
+        let messages = if message_quantity == 0 {
+            Vec::new()
+        } else {
+            data[Self::HEADER_LENGTH..body_end]
+                .chunks(message_size as usize)
+                .map(|frame| AnyMessage::from_bytes_with(frame, &format))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
         // 解析尾部
-        let checksum = u16::from_le_bytes([data[85], data[86]]);
-        let reserved = [data[87], data[88], data[89]];
+        let reserved = if format.is_astm() {
+            [0; 3]
+        } else {
+            let reserved_start = body_end + integrity.checksum_length();
+            [data[reserved_start], data[reserved_start + 1], data[reserved_start + 2]]
+        };
 
         Ok(Self {
-            protocol_version,
+            format,
             message_counter,
-            message_size,
-            message_quantity,
-            base_message: base,
-            system_message: system,
-            position_message: position,
+            messages,
             checksum,
             reserved,
+            timestamp_policy: TimestampPolicy::Provided,
+            corrupt_checksum: false,
         })
     }
 
-    fn encode(&self) -> Vec<u8> {
-        self.print();
+    /// 子消息统一按整包的线上格式编码，`format` 参数不生效
+    fn encode_with(&self, _format: &WireFormat) -> Result<Vec<u8>, MessageError> {
+        // 帧数量只占1字节，且标准限制每包最多9帧
+        if self.messages.len() > Self::MAX_MESSAGES {
+            return Err(MessageError::TooManyMessages(Self::MAX_MESSAGES, self.messages.len()));
+        }
+
         let mut bytes = Vec::new();
-        
+
         // 编码头部
-        let rid_counter: u8 = RID_COUNTER.fetch_add(0x01, Ordering::SeqCst); // 序列号按802.11规范递增
-
-        bytes.push(rid_counter);
-        bytes.push(self.protocol_version);
-        
-        bytes.push(self.message_size);
-        bytes.push(self.message_quantity);
-        
-        // 编码子消息
-        bytes.extend(self.base_message.encode());
-        bytes.extend(self.position_message.encode());
-        bytes.extend(self.system_message.encode());
-
-        
-        // 计算校验和
-        let checksum = crc16::State::<crc16::XMODEM>::calculate(&bytes);
-        bytes.extend_from_slice(&checksum.to_le_bytes());
-        
+        bytes.push(self.message_counter);
+        bytes.push((Self::PACK_MESSAGE_TYPE << 4) | self.format.version.nibble());
+
+        bytes.push(Self::MESSAGE_SIZE);
+        bytes.push(self.messages.len() as u8);
+
+        // 编码子消息，按时间戳策略更新时间戳
+        let now = self.timestamp_policy.now();
+        for message in &self.messages {
+            match now {
+                Some(time) => {
+                    let mut message = message.clone();
+                    message.apply_timestamp(time);
+                    bytes.extend(message.encode_with(&self.format)?);
+                }
+                None => bytes.extend(message.encode_with(&self.format)?),
+            }
+        }
+
+        // 计算校验值，故障注入时写入错误的值
+        let integrity = self.format.integrity();
+        let mut checksum = integrity.compute(&bytes);
+        if self.corrupt_checksum {
+            checksum = integrity.corrupt(checksum);
+        }
+        integrity.write(checksum, &mut bytes);
+
         // 添加预留字段
-        bytes.extend_from_slice(&self.reserved);
-        
-        bytes
-    }
-
-
-
-    fn print(&self) {
-        println!("=== Packet Message ===");
-        println!("Protocol Version: 0x{:02X}", self.protocol_version);
-        println!("Message Counter: {}", self.message_counter);
-        println!("Total Size: {} bytes", self.message_size);
-        println!("Contains {} messages", self.message_quantity);
-        
-        println!("\nBase Message:");
-        self.base_message.print();
-        
-        println!("\nSystem Message:");
-        self.system_message.print();
-        
-        println!("\nPosition Message:");
-        self.position_message.print();
-        
-        println!("\nChecksum: 0x{:04X}", self.checksum);
+        if !self.format.is_astm() {
+            bytes.extend_from_slice(&self.reserved);
+        }
+
+        Ok(bytes)
+    }
+
+
+
+    fn describe(&self) -> FieldNode {
+        let integrity = self.format.integrity();
+        let mut checksum = FieldNode::field("checksum", "校验值", format!("{:?}", integrity)).raw(self.checksum);
+        if self.corrupt_checksum {
+            checksum = checksum.child(FieldNode::field("corrupt_checksum", "故障注入", "写入错误的校验值"));
+        }
+        FieldNode::group("packet", "整包报文")
+            .child(FieldNode::field("message_counter", "消息计数器", self.message_counter))
+            .child(FieldNode::field("protocol_version", "协议版本", format!("{:?}", self.format.version))
+                .raw((Self::PACK_MESSAGE_TYPE << 4) | self.format.version.nibble()))
+            .child(FieldNode::field("encoding_profile", "编码风格", format!("{:?}", self.format.profile)))
+            .child(FieldNode::field("message_size", "每帧大小", format!("{} 字节", Self::MESSAGE_SIZE)))
+            .child(FieldNode::field("message_quantity", "帧数量", self.messages.len()))
+            .child(FieldNode::group("messages", "子消息").children(self.messages.iter().map(|message| message.describe_with(&self.format))))
+            .child(checksum)
+            .child(FieldNode::group("reserved", "预留").raw_bytes(&self.reserved))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::field_types::{
+        ClassificationRegion, HorizontalAccuracy, IdType, RunStatus, SpeedAccuracy, StationType, UaType, VerticalAccuracy,
+    };
+    use chrono::{TimeZone, Utc};
+    use proptest::prelude::*;
+
+    // sample() 编码得到的整包 (基本、位置向量、系统报文各一条)
+    const SAMPLE_PACKET: &str = concat!(
+        "01f11903",
+        "0112313538314635464b443232393430303041314232000000",
+        "11225a28fdc041c817c0f851450000780000005a0339300000",
+        "4109c041c817c0f85145010032b004000012c7018085746700",
+        "125f000000",
+    );
+
+    fn sample_messages() -> Vec<AnyMessage> {
+        let base = BaseMessage {
+            id_type: IdType::SerialNumber,
+            ua_type: UaType::HelicopterOrMultirotor,
+            uas_id: "1581F5FKD2294000A1B2".to_string(),
+            reserved: [0; 3],
+        };
+        let mut position = PositionVectorMessage::from_physical(39.9, 116.3, 120.0, 270.0, 10.0, -1.5).unwrap();
+        position.run_status = RunStatus::Airborne;
+        position.vertical_accuracy = VerticalAccuracy::LessThan3m;
+        position.horizontal_accuracy = HorizontalAccuracy::LessThan10m;
+        position.speed_accuracy = SpeedAccuracy::LessThan1mps;
+        position.timestamp = 12345;
+        let system = SystemMessage::builder()
+            .classification_region(ClassificationRegion::China)
+            .station_type(StationType::LiveGnss)
+            .operator_position(39.9, 116.3)
+            .operation_area(1, 500.0)
+            .altitude_limits(0.0, 120.0)
+            .ua_classification(1, 2)
+            .station_altitude(45.5)
+            .timestamp(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap())
+            .build()
+            .unwrap();
+        vec![base.into(), position.into(), system.into()]
+    }
+
+    fn sample() -> PacketMessage {
+        PacketMessage::builder()
+            .timestamp_policy(TimestampPolicy::Provided)
+            .messages(sample_messages())
+            .build()
+            .unwrap()
+    }
+
+    fn any_message() -> impl Strategy<Value = AnyMessage> {
+        prop_oneof![
+            ("[A-Z0-9]{0,20}", prop_oneof![0u8..3, Just(4u8)], 0u8..16).prop_map(|(uas_id, id_type, ua_type)| {
+                AnyMessage::from(BaseMessage {
+                    id_type: IdType::from_bits(id_type),
+                    ua_type: UaType::from_bits(ua_type),
+                    uas_id,
+                    reserved: [0; 3],
+                })
+            }),
+            // 仿大疆布局的高度为整米
+            (-90.0f64..=90.0, -180.0f64..=180.0, -1000i16..=5000, 0.0f32..360.0).prop_map(
+                |(latitude, longitude, altitude, track)| {
+                    AnyMessage::from(
+                        PositionVectorMessage::from_physical(latitude, longitude, altitude as f32, track, 12.0, 0.5).unwrap(),
+                    )
+                }
+            ),
+            (-90.0f64..=90.0, -180.0f64..=180.0, any::<u32>()).prop_map(|(latitude, longitude, timestamp)| {
+                let mut system = SystemMessage::builder().operator_position(latitude, longitude).build().unwrap();
+                system.timestamp = timestamp;
+                AnyMessage::from(system)
+            }),
+        ]
+    }
+
+    fn any_integrity() -> impl Strategy<Value = Integrity> {
+        prop_oneof![
+            Just(Integrity::None),
+            Just(Integrity::Crc16Xmodem),
+            Just(Integrity::Crc16CcittFalse),
+            Just(Integrity::Crc16Kermit),
+            Just(Integrity::Crc16Arc),
+            Just(Integrity::Crc32),
+        ]
+    }
+
+    #[test]
+    fn encodes_sample_packet() {
+        assert_eq!(hex::encode(sample().encode().unwrap()), SAMPLE_PACKET);
+    }
+
+    #[test]
+    fn decodes_sample_packet() {
+        let bytes = hex::decode(SAMPLE_PACKET).unwrap();
+        let packet = PacketMessage::from_bytes_strict(&bytes).unwrap();
+        assert_eq!(packet.messages(), sample_messages().as_slice());
+        assert_eq!(packet.checksum(), 0x5f12);
+        assert_eq!(packet.get_ssid(), "RID-1581F5FKD2294000A1B2");
+    }
+
+    #[test]
+    fn empty_packet_has_header_and_trailer_only() {
+        let packet = PacketMessage::builder().build().unwrap();
+        assert_eq!(packet.encode().unwrap().len(), PacketMessage::HEADER_LENGTH + 2 + PacketMessage::RESERVED_LENGTH);
+    }
+
+    #[test]
+    fn message_count_is_limited() {
+        let messages = vec![sample_messages()[0].clone(); PacketMessage::MAX_MESSAGES + 1];
+        assert_eq!(
+            PacketMessage::builder().messages(messages).build(),
+            Err(MessageError::TooManyMessages(9, 10))
+        );
+    }
+
+    #[test]
+    fn decodes_header_with_zero_size_and_quantity() {
+        let astm = WireFormat::astm(ProtocolVersion::F3411_22a);
+        let packet = PacketMessage::from_bytes_with(&[0x00, 0xf2, 0x00, 0x00], &astm).unwrap();
+        assert!(packet.messages().is_empty());
+
+        let mut bytes = vec![0x00, 0xf1, 0x00, 0x00];
+        let integrity = WireFormat::default().integrity();
+        integrity.write(integrity.compute(&bytes), &mut bytes);
+        bytes.extend_from_slice(&[0; 3]);
+        assert!(PacketMessage::from_bytes(&bytes).unwrap().messages().is_empty());
+    }
+
+    #[test]
+    fn strict_decode_reports_unknown_protocol_version() {
+        let mut bytes = hex::decode(SAMPLE_PACKET).unwrap();
+        bytes[1] = 0xf7;
+        assert_eq!(PacketMessage::from_bytes_strict(&bytes), Err(MessageError::ProtocolVersionMismatch(0x01, 0x07)));
+
+        let mut frame = bytes[4..29].to_vec();
+        frame[0] = 0x07;
+        assert_eq!(AnyMessage::from_bytes_strict(&frame), Err(MessageError::ProtocolVersionMismatch(0x01, 0x07)));
+    }
+
+    #[test]
+    fn astm_version_one_decodes_as_f3411_20() {
+        let format = WireFormat::astm(ProtocolVersion::F3411_20);
+        let packet = PacketMessage::builder().wire_format(format).messages(sample_messages()).build().unwrap();
+        let bytes = packet.encode().unwrap();
+        assert_eq!(bytes[1], 0xf1);
+
+        let decoded = PacketMessage::from_bytes_strict_with(&bytes, &WireFormat::astm(ProtocolVersion::F3411_22a)).unwrap();
+        assert_eq!(decoded.wire_format().version, ProtocolVersion::F3411_20);
+        assert_eq!(decoded.encode().unwrap(), bytes);
+        let decoded = PacketMessage::from_bytes(&hex::decode(SAMPLE_PACKET).unwrap()).unwrap();
+        assert_eq!(decoded.wire_format().version, ProtocolVersion::Gb42590);
+    }
+
+    // 真机抓包放在 tests/fixtures/captures/<编码风格>/ 下，说明见 tests/fixtures/README.md
+    #[test]
+    fn real_captures_round_trip() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/captures");
+        let formats = [("dji_compat", WireFormat::default()), ("astm_f3411", WireFormat::astm(ProtocolVersion::F3411_22a))];
+        for (profile, format) in formats {
+            let Ok(entries) = std::fs::read_dir(root.join(profile)) else { continue };
+            for entry in entries {
+                let path = entry.unwrap().path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("hex") {
+                    continue;
+                }
+                let bytes = hex::decode(std::fs::read_to_string(&path).unwrap().trim()).unwrap();
+                let packet = PacketMessage::from_bytes_with(&bytes, &format)
+                    .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+                assert_eq!(packet.encode().unwrap(), bytes, "{}", path.display());
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_packet() {
+        let bytes = hex::decode(SAMPLE_PACKET).unwrap();
+        assert_eq!(
+            PacketMessage::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(MessageError::InsufficientLength(bytes.len(), bytes.len() - 1))
+        );
+    }
+
+    #[test]
+    fn rejects_corrupted_body() {
+        let mut bytes = hex::decode(SAMPLE_PACKET).unwrap();
+        bytes[10] ^= 0x01;
+        assert!(matches!(PacketMessage::from_bytes(&bytes), Err(MessageError::ChecksumMismatch(..))));
+    }
+
+    #[test]
+    fn rejects_injected_bad_checksum() {
+        let mut packet = sample();
+        packet.set_corrupt_checksum(true);
+        assert!(matches!(
+            PacketMessage::from_bytes(&packet.encode().unwrap()),
+            Err(MessageError::ChecksumMismatch(..))
+        ));
+    }
+
+    #[test]
+    fn encode_reports_invalid_sub_message() {
+        let mut packet = sample();
+        if let Some(AnyMessage::Base(base)) = packet.message_mut(0) {
+            base.uas_id = "1581F5FKD2294000A1B2C3".to_string();
+        }
+        assert_eq!(packet.encode(), Err(MessageError::DataTooLong(20, 22)));
+    }
+
+    #[test]
+    fn describes_decoded_and_raw_values() {
+        let packet = PacketMessage::from_bytes(&hex::decode(SAMPLE_PACKET).unwrap()).unwrap();
+        let description = packet.describe();
+        assert_eq!(description.get("checksum").unwrap().raw.as_deref(), Some("0x5F12"));
+
+        let messages = &description.get("messages").unwrap().children;
+        assert_eq!(messages.len(), 3);
+        let latitude = messages[1].get("latitude").unwrap();
+        assert_eq!(latitude.value.as_deref(), Some("39.9000000°"));
+        assert_eq!(latitude.raw.as_deref(), Some("0x17C841C0"));
+        assert_eq!(messages[0].get("uas_id").unwrap().value.as_deref(), Some("1581F5FKD2294000A1B2"));
+    }
+
+    #[test]
+    fn json_ingest_validates_uas_id() {
+        let messages = sample_messages();
+        let mut json = serde_json::json!({
+            "base_message": messages[0].clone(),
+            "position_message": messages[1].clone(),
+            "system_message": messages[2].clone(),
+        });
+        assert!(serde_json::from_value::<PacketMessage>(json.clone()).is_ok());
+
+        json["base_message"]["uas_id"] = "1581F5FKD229400".into();
+        let error = serde_json::from_value::<PacketMessage>(json).unwrap_err();
+        assert!(error.to_string().contains("UAS ID 格式错误"), "{}", error);
+    }
+
+    #[test]
+    fn json_ingest_accepts_message_list() {
+        let serial = sample_messages()[0].clone();
+        let session = AnyMessage::from(BaseMessage {
+            id_type: IdType::SpecificSession,
+            ua_type: UaType::HelicopterOrMultirotor,
+            uas_id: "SESSION-0001".to_string(),
+            reserved: [0; 3],
+        });
+        let position = sample_messages()[1].clone();
+        let json = serde_json::json!({ "messages": [serial, session, position] });
+        let packet = serde_json::from_value::<PacketMessage>(json).unwrap();
+        assert_eq!(packet.messages(), [serial, session, position].as_slice());
+
+        let mut json = serde_json::json!({ "messages": [sample_messages()[0].clone()] });
+        json["messages"][0]["uas_id"] = "1581F5FKD229400".into();
+        assert!(serde_json::from_value::<PacketMessage>(json).is_err());
+    }
+
+    #[test]
+    fn json_round_trips() {
+        // ASTM 布局中版本号 0x1 表示 F3411-20
+        let packet = PacketMessage::builder()
+            .protocol_version(ProtocolVersion::F3411_20)
+            .profile(EncodingProfile::AstmF3411)
+            .integrity(Integrity::Crc32)
+            .message_counter(7)
+            .timestamp_policy(TimestampPolicy::Provided)
+            .messages(sample_messages())
+            .build()
+            .unwrap();
+        let json = serde_json::to_value(&packet).unwrap();
+        assert_eq!(json["messages"].as_array().unwrap().len(), 3);
+        assert_eq!(serde_json::from_value::<PacketMessage>(json).unwrap(), packet);
+    }
+
+    #[test]
+    fn spreads_auth_pages_over_consecutive_packs() {
+        let data: Vec<u8> = (0..=254).collect();
+        let pages = AuthenticationMessage::paginate(1, 0, &data).unwrap();
+        assert_eq!(pages.len(), 12);
+        let messages = sample_messages();
+        let json = serde_json::json!({
+            "base_message": messages[0].clone(),
+            "position_message": messages[1].clone(),
+            "system_message": messages[2].clone(),
+            "authentication_messages": pages,
+        });
+        assert!(serde_json::from_value::<PacketMessage>(json.clone()).is_err());
+
+        let packs = serde_json::from_value::<PacketSequence>(json).unwrap().into_packs();
+        assert_eq!(packs.len(), 2);
+        assert_eq!(packs[0].messages().len(), PacketMessage::MAX_MESSAGES);
+        assert_eq!(packs[0].messages()[..3], messages[..]);
+        assert_eq!(packs[1].messages().len(), 7);
+        assert_eq!(packs[1].messages()[0], messages[0]);
+        assert_eq!(packs[1].get_ssid(), packs[0].get_ssid());
+
+        let received: Vec<AuthenticationMessage> = packs
+            .iter()
+            .flat_map(|pack| pack.messages())
+            .filter_map(|message| match message {
+                AnyMessage::Authentication(page) => Some(page.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(AuthenticationMessage::reassemble(&received), data);
+    }
+
+    #[test]
+    fn sequence_rejects_too_many_other_messages() {
+        let messages = vec![sample_messages()[1].clone(); PacketMessage::MAX_MESSAGES + 1];
+        assert_eq!(
+            PacketMessage::builder().messages(messages).build_sequence(),
+            Err(MessageError::TooManyMessages(9, 10))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips(
+            messages in prop::collection::vec(any_message(), 0..=PacketMessage::MAX_MESSAGES),
+            counter in any::<u8>(),
+            reserved in any::<[u8; 3]>(),
+            integrity in any_integrity(),
+        ) {
+            let packet = PacketMessage::builder()
+                .message_counter(counter)
+                .reserved(reserved)
+                .integrity(integrity)
+                .timestamp_policy(TimestampPolicy::Provided)
+                .messages(messages)
+                .build()
+                .unwrap();
+            let format = packet.wire_format();
+            let encoded = packet.encode().unwrap();
+            let decoded = PacketMessage::from_bytes_with(&encoded, &format).unwrap();
+            let body_end = encoded.len() - PacketMessage::trailer_length(&format);
+            prop_assert_eq!(decoded.messages(), packet.messages());
+            prop_assert_eq!(decoded.message_counter, counter);
+            prop_assert_eq!(decoded.reserved, reserved);
+            prop_assert_eq!(decoded.checksum(), format.integrity().compute(&encoded[..body_end]));
+            prop_assert_eq!(decoded.encode().unwrap(), encoded);
+        }
     }
 }
@@ -1,36 +1,42 @@
+use crate::message::field_types::{check_coded, HeightType, HorizontalAccuracy, RunStatus, SpeedAccuracy, VerticalAccuracy};
 use crate::message::message::MessageType;
 
+use super::bitfield::BitField;
+use super::describe::FieldNode;
 use super::message::{Message, MessageError};
+use super::protocol::WireFormat;
+use super::units::{decode_astm_altitude, decode_degrees, encode_astm_altitude, encode_degrees, ensure_finite};
 use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Timelike, Utc};
 
 /// 位置向量报文，强 制 动 态 报 文 ，主要包含位置 ， 高度 ， 速度 ， 时间等标识 UA 运行情况的报文 。
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct PositionVectorMessage {
     // 第1字节 (运行状态和标志位)
-    pub run_status: u8,         // 运行状态 (7-4位)
+    pub run_status: RunStatus,  // 运行状态 (7-4位)
     #[serde(default)]
-    pub reserved_flag: bool,     // 预留标志位 (3位)
-    pub height_type: u8,        // 高度类型位 (2位) - 0-3
-    pub track_direction: u8,   // 航迹角 E/W 方向标志 (1位)
-    pub speed_multiplier: u8,  // 速度乘数 (0位)
+    pub reserved_flag: bool,     // 预留标志位 (第3位)
+    pub height_type: HeightType, // 高度类型位 (第2位)
+    pub track_direction: u8,   // 航迹角 E/W 方向标志 (第1位)
+    pub speed_multiplier: u8,  // 速度乘数 (第0位)
 
     // 第2-4字节
     pub track_angle: u8,        // 航迹角 (1字节)
-    pub ground_speed: i8,       // 地速 (1字节, 有正负)
-    pub vertical_speed: i8,     // 垂直速度 (1字节, 有正负, 可选)
+    pub ground_speed: u8,       // 地速 (1字节, 乘数为0时 0.25 m/s, 为1时 0.75 m/s + 63.75 m/s)
+    pub vertical_speed: i8,     // 垂直速度 (1字节, 有正负, 0.5 m/s, 可选)
 
     // 第5-18字节
-    pub latitude: i32,           // 纬度 (4字节小端序)
-    pub longitude: i32,          // 经度 (4字节小端序)
-    pub pressure_altitude: i16, // 气压高度 (2字节小端序, 可选)
-    pub geometric_altitude: i16, // 几何高度 (2字节小端序, 可选)
-    pub ground_altitude: i16,    // 距地高度 (2字节小端序)
+    pub latitude: i32,           // 纬度 (4字节小端序, 1e-7 度)
+    pub longitude: i32,          // 经度 (4字节小端序, 1e-7 度)
+    pub pressure_altitude: f32,  // 气压高度 (米, 可选, 线上为2字节小端序)
+    pub geometric_altitude: f32, // 几何高度 (米, 可选, 线上为2字节小端序)
+    pub ground_altitude: f32,    // 距地高度 (米, 线上为2字节小端序)
 
     // 第19-22字节
-    pub vertical_accuracy: u8,   // 垂直精度 (7-4位, 4 bits)
-    pub horizontal_accuracy: u8, // 水平精度 (3-0位, 4 bits)
-    pub speed_accuracy: u8,      // 速度精度 (3-0位, 4 bits)
-    pub timestamp: u16,          // 时间戳 (2字节小端序)
+    pub vertical_accuracy: VerticalAccuracy,     // 垂直精度 (7-4位, 4 bits)
+    pub horizontal_accuracy: HorizontalAccuracy, // 水平精度 (3-0位, 4 bits)
+    pub speed_accuracy: SpeedAccuracy,           // 速度精度 (3-0位, 4 bits)
+    pub timestamp: u16,          // 时间戳 (2字节小端序, 整点后的 0.1 秒数)
 
     // 第23-24字节
     #[serde(default)]
@@ -43,26 +49,208 @@ impl PositionVectorMessage {
     pub const MESSAGE_TYPE: u8 = 0x01;
     const EXPECTED_LENGTH: usize = 24;
 
-    fn calculate_full_track_angle(&self) -> u16 {
+    // 第1字节位段
+    const RUN_STATUS: BitField = BitField::new(4, 4);
+    const RESERVED_FLAG: BitField = BitField::new(3, 1);
+    const HEIGHT_TYPE: BitField = BitField::new(2, 1);
+    const TRACK_DIRECTION: BitField = BitField::new(1, 1);
+    const SPEED_MULTIPLIER: BitField = BitField::new(0, 1);
+
+    // 地速分辨率: 乘数为0时 0.25 m/s, 乘数为1时 0.75 m/s 且偏移 63.75 m/s
+    const SPEED_RESOLUTION: f32 = 0.25;
+    const SPEED_RESOLUTION_MULTIPLIED: f32 = 0.75;
+    const SPEED_MULTIPLIER_OFFSET: f32 = 255.0 * Self::SPEED_RESOLUTION;
+    // 地速最大编码值 (255 表示未知)
+    const MAX_GROUND_SPEED: u8 = 254;
+    // 垂直速度分辨率 0.5 m/s, 最大 ±62 m/s (±63 表示未知)
+    const VERTICAL_SPEED_RESOLUTION: f32 = 0.5;
+    const MAX_VERTICAL_SPEED: i8 = 124;
+    // 高度分辨率 (米)，仿大疆布局线上为整米
+    const ALTITUDE_RESOLUTION: f32 = 0.5;
+    // 时间戳最大值 (59分59.9秒)
+    const MAX_TIMESTAMP: u32 = 35999;
+
+    /// 按物理量构造位置向量报文，其余字段取默认值
+    ///
+    /// # 参数
+    /// - `latitude_deg` / `longitude_deg`: 纬度/经度 (度)
+    /// - `geometric_altitude_m`: 几何高度 (米)
+    /// - `track_deg`: 航迹角 (度, 正北顺时针)
+    /// - `ground_speed_mps` / `vertical_speed_mps`: 地速/垂直速度 (米/秒)
+    ///
+    /// # 错误
+    /// 经纬度超出范围、数值非有限或地速为负时返回 MessageError::ValueOutOfRange
+    pub fn from_physical(
+        latitude_deg: f64,
+        longitude_deg: f64,
+        geometric_altitude_m: f32,
+        track_deg: f32,
+        ground_speed_mps: f32,
+        vertical_speed_mps: f32,
+    ) -> Result<Self, MessageError> {
+        let mut message = Self::default();
+        message.set_latitude_deg(latitude_deg)?;
+        message.set_longitude_deg(longitude_deg)?;
+        message.set_geometric_altitude_m(geometric_altitude_m)?;
+        message.set_track_deg(track_deg)?;
+        message.set_ground_speed_mps(ground_speed_mps)?;
+        message.set_vertical_speed_mps(vertical_speed_mps)?;
+        Ok(message)
+    }
+
+    /// 设置纬度 (度, -90 ~ 90)
+    pub fn set_latitude_deg(&mut self, degrees: f64) -> Result<(), MessageError> {
+        self.latitude = encode_degrees("latitude", degrees, 90.0)?;
+        Ok(())
+    }
+
+    /// 设置经度 (度, -180 ~ 180)
+    pub fn set_longitude_deg(&mut self, degrees: f64) -> Result<(), MessageError> {
+        self.longitude = encode_degrees("longitude", degrees, 180.0)?;
+        Ok(())
+    }
+
+    /// 设置航迹角 (度)，拆分为 0-179 的角度和 E/W 标志，超出 0-360 的值按圆周折算
+    pub fn set_track_deg(&mut self, degrees: f32) -> Result<(), MessageError> {
+        ensure_finite("track_angle", degrees as f64)?;
+        let track = (degrees.round() as i32).rem_euclid(360) as u16;
+        self.track_direction = (track >= 180) as u8;
+        self.track_angle = (track % 180) as u8;
+        Ok(())
+    }
+
+    /// 设置地速 (米/秒)，自动选择速度乘数，超出上限时饱和
+    pub fn set_ground_speed_mps(&mut self, speed: f32) -> Result<(), MessageError> {
+        ensure_finite("ground_speed", speed as f64)?;
+        if speed < 0.0 {
+            return Err(MessageError::ValueOutOfRange("ground_speed", speed as f64));
+        }
+
+        let low_speed_max = Self::SPEED_RESOLUTION * Self::MAX_GROUND_SPEED as f32;
+        if speed <= low_speed_max {
+            self.speed_multiplier = 0;
+            self.ground_speed = (speed / Self::SPEED_RESOLUTION).round() as u8;
+        } else {
+            self.speed_multiplier = 1;
+            let encoded = ((speed - Self::SPEED_MULTIPLIER_OFFSET) / Self::SPEED_RESOLUTION_MULTIPLIED).round();
+            self.ground_speed = encoded.clamp(0.0, Self::MAX_GROUND_SPEED as f32) as u8;
+        }
+        Ok(())
+    }
+
+    /// 设置垂直速度 (米/秒, 上升为正)，超出 ±62 m/s 时饱和
+    pub fn set_vertical_speed_mps(&mut self, speed: f32) -> Result<(), MessageError> {
+        ensure_finite("vertical_speed", speed as f64)?;
+        let max = Self::MAX_VERTICAL_SPEED as f32;
+        self.vertical_speed = (speed / Self::VERTICAL_SPEED_RESOLUTION).round().clamp(-max, max) as i8;
+        Ok(())
+    }
+
+    /// 设置气压高度 (米)，按 0.5 米取整，编码时超出范围饱和
+    pub fn set_pressure_altitude_m(&mut self, altitude: f32) -> Result<(), MessageError> {
+        self.pressure_altitude = Self::encode_altitude("pressure_altitude", altitude)?;
+        Ok(())
+    }
+
+    /// 设置几何高度 (米)，按 0.5 米取整，编码时超出范围饱和
+    pub fn set_geometric_altitude_m(&mut self, altitude: f32) -> Result<(), MessageError> {
+        self.geometric_altitude = Self::encode_altitude("geometric_altitude", altitude)?;
+        Ok(())
+    }
+
+    /// 设置距地高度 (米)，按 0.5 米取整，编码时超出范围饱和
+    pub fn set_ground_altitude_m(&mut self, altitude: f32) -> Result<(), MessageError> {
+        self.ground_altitude = Self::encode_altitude("ground_altitude", altitude)?;
+        Ok(())
+    }
+
+    /// 按 UTC 时间设置时间戳，取整点后经过的 0.1 秒数
+    pub fn set_timestamp_utc(&mut self, time: DateTime<Utc>) {
+        let tenths = time.minute() * 600 + time.second() * 10 + time.nanosecond() / 100_000_000;
+        self.timestamp = tenths.min(Self::MAX_TIMESTAMP) as u16;
+    }
+
+    /// 纬度 (度)
+    pub fn latitude_deg(&self) -> f64 {
+        decode_degrees(self.latitude)
+    }
+
+    /// 经度 (度)
+    pub fn longitude_deg(&self) -> f64 {
+        decode_degrees(self.longitude)
+    }
+
+    /// 完整航迹角 (度, 0-359)
+    pub fn track_deg(&self) -> u16 {
         if self.track_direction == 1 {
             self.track_angle as u16 + 180
         } else {
             self.track_angle as u16
         }
     }
-    
-    fn calculate_ground_speed_knots(&self) -> f32 {
-        if self.speed_multiplier == 1{
-            self.ground_speed as f32 * 10.0
+
+    /// 地速 (米/秒)
+    pub fn ground_speed_mps(&self) -> f32 {
+        if self.speed_multiplier == 1 {
+            self.ground_speed as f32 * Self::SPEED_RESOLUTION_MULTIPLIED + Self::SPEED_MULTIPLIER_OFFSET
         } else {
-            self.ground_speed as f32
+            self.ground_speed as f32 * Self::SPEED_RESOLUTION
         }
     }
-    
 
+    /// 垂直速度 (米/秒)
+    pub fn vertical_speed_mps(&self) -> f32 {
+        self.vertical_speed as f32 * Self::VERTICAL_SPEED_RESOLUTION
+    }
+
+    /// 气压高度 (米)
+    pub fn pressure_altitude_m(&self) -> f32 {
+        self.pressure_altitude
+    }
+
+    /// 几何高度 (米)
+    pub fn geometric_altitude_m(&self) -> f32 {
+        self.geometric_altitude
+    }
+
+    /// 距地高度 (米)
+    pub fn ground_altitude_m(&self) -> f32 {
+        self.ground_altitude
+    }
+
+    // 按两种布局中较细的 0.5 米分辨率取整
+    fn encode_altitude(field: &'static str, altitude: f32) -> Result<f32, MessageError> {
+        ensure_finite(field, altitude as f64)?;
+        Ok((altitude / Self::ALTITUDE_RESOLUTION).round() * Self::ALTITUDE_RESOLUTION)
+    }
+
+    // 高度的线上编码，ASTM 为 0.5 米分辨率、偏移 -1000 米，否则为有符号整米，超出范围时饱和
+    fn encode_wire_altitude(format: &WireFormat, altitude: f32) -> [u8; 2] {
+        if format.is_astm() {
+            encode_astm_altitude(altitude).to_le_bytes()
+        } else {
+            (altitude.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16).to_le_bytes()
+        }
+    }
 
+    fn decode_wire_altitude(format: &WireFormat, bytes: [u8; 2]) -> f32 {
+        if format.is_astm() {
+            decode_astm_altitude(u16::from_le_bytes(bytes))
+        } else {
+            i16::from_le_bytes(bytes) as f32
+        }
+    }
 }
 
+// 第1字节的位段不得重叠，编译期检查
+const _: () = BitField::assert_disjoint(&[
+    PositionVectorMessage::RUN_STATUS,
+    PositionVectorMessage::RESERVED_FLAG,
+    PositionVectorMessage::HEIGHT_TYPE,
+    PositionVectorMessage::TRACK_DIRECTION,
+    PositionVectorMessage::SPEED_MULTIPLIER,
+]);
+
 impl Message for PositionVectorMessage {
     /// 从u8数组解析为PositionVectorMessage
     ///
@@ -73,7 +261,7 @@ impl Message for PositionVectorMessage {
     ///
     /// # 错误
     /// 当输入数据长度不足时返回ParseError
-    fn from_bytes(data: &[u8]) -> Result<Self, MessageError>  {
+    fn from_bytes_with(data: &[u8], format: &WireFormat) -> Result<Self, MessageError> {
         // 验证数据长度
         if data.len() < Self::EXPECTED_LENGTH {
             return Err(MessageError::InsufficientLength(Self::EXPECTED_LENGTH, data.len()));
@@ -81,15 +269,15 @@ impl Message for PositionVectorMessage {
 
         // 解析第1字节 (运行状态和标志位)
         let byte0 = data[0];
-        let run_status = (byte0 >> 4) & 0x0F; // 7-4位: 运行状态
-        let reserved_flag = (byte0 & 0x08) != 0; // 3位: 预留标志位
-        let height_type = (byte0 & 0x06) >> 1; // 2位: 高度类型位 (00, 01, 10, 11)
-        let track_direction = byte0 & 0x01 ; // 1位: 航迹角方向标志
-        let speed_multiplier = byte0 & 0x01 ; // 0位: 速度乘数
+        let run_status = RunStatus::from_bits(Self::RUN_STATUS.get(byte0));
+        let reserved_flag = Self::RESERVED_FLAG.get(byte0) != 0;
+        let height_type = HeightType::from_bits(Self::HEIGHT_TYPE.get(byte0));
+        let track_direction = Self::TRACK_DIRECTION.get(byte0);
+        let speed_multiplier = Self::SPEED_MULTIPLIER.get(byte0);
 
         // 解析后续字节
         let track_angle = data[1];         // 第2字节: 航迹角 (0-179)
-        let ground_speed = data[2] as i8;  // 第3字节: 地速 (有符号)
+        let ground_speed = data[2];        // 第3字节: 地速
         let vertical_speed = data[3] as i8; // 第4字节: 垂直速度 (有符号)
 
         // 解析纬度、经度 (小端序)
@@ -101,17 +289,17 @@ impl Message for PositionVectorMessage {
         ]);
 
         // 解析高度值 (小端序)
-        let pressure_altitude = i16::from_le_bytes([data[12], data[13]]);
-        let geometric_altitude = i16::from_le_bytes([data[14], data[15]]);
-        let ground_altitude = i16::from_le_bytes([data[16], data[17]]);
+        let pressure_altitude = Self::decode_wire_altitude(format, [data[12], data[13]]);
+        let geometric_altitude = Self::decode_wire_altitude(format, [data[14], data[15]]);
+        let ground_altitude = Self::decode_wire_altitude(format, [data[16], data[17]]);
 
         // 解析精度值
         let byte18 = data[18];
-        let vertical_accuracy = byte18 >> 4;     // 高4位: 垂直精度
-        let horizontal_accuracy = byte18 & 0x0F; // 低4位: 水平精度
+        let vertical_accuracy = VerticalAccuracy::from_bits(byte18 >> 4);     // 高4位: 垂直精度
+        let horizontal_accuracy = HorizontalAccuracy::from_bits(byte18 & 0x0F); // 低4位: 水平精度
         
         let byte19 = data[19];
-        let speed_accuracy = byte19 & 0x0F;      // 低4位: 速度精度
+        let speed_accuracy = SpeedAccuracy::from_bits(byte19 & 0x0F);      // 低4位: 速度精度
 
         // 解析时间戳和小端序
         let timestamp = u16::from_le_bytes([data[20], data[21]]);
@@ -144,23 +332,28 @@ impl Message for PositionVectorMessage {
         })
     }
 
-    fn encode(&self) -> Vec<u8> {
+    fn encode_with(&self, format: &WireFormat) -> Result<Vec<u8>, MessageError> {
         let mut bytes = Vec::new();
         
-        let message_type = MessageType::PositionVectorMessageType as u8;
-        let message_protocol = (message_type << 4) | 0x01;
+        let message_protocol = format.header(MessageType::PositionVectorMessageType);
         bytes.push(message_protocol);
-        // 第1字节编码
-        let mut byte1 = (self.run_status << 4) as u8;
-        byte1 |= (self.reserved_flag as u8) << 3;
-        byte1 |= (self.height_type & 0x03) << 2;
-        byte1 |= self.track_direction << 1;
-        byte1 |= self.speed_multiplier;
+        // 第1字节编码，单比特标志超出 0/1 时拒绝编码，不静默截掉高位
+        for (field, value) in [("track_direction", self.track_direction), ("speed_multiplier", self.speed_multiplier)] {
+            if value > 1 {
+                return Err(MessageError::InvalidFieldValue(field, value));
+            }
+        }
+        let mut byte1 = 0;
+        Self::RUN_STATUS.set(&mut byte1, u8::from(self.run_status));
+        Self::RESERVED_FLAG.set(&mut byte1, self.reserved_flag as u8);
+        Self::HEIGHT_TYPE.set(&mut byte1, u8::from(self.height_type));
+        Self::TRACK_DIRECTION.set(&mut byte1, self.track_direction);
+        Self::SPEED_MULTIPLIER.set(&mut byte1, self.speed_multiplier);
         bytes.push(byte1);
         
         // 第2-4字节
         bytes.push(self.track_angle);
-        bytes.push(self.ground_speed as u8);
+        bytes.push(self.ground_speed);
         bytes.push(self.vertical_speed as u8);
         
         // 经纬度编码（小端序）
@@ -168,42 +361,265 @@ impl Message for PositionVectorMessage {
         bytes.extend_from_slice(&self.longitude.to_le_bytes());
         
         // 高度字段
-        bytes.extend_from_slice(&self.pressure_altitude.to_le_bytes());
-        bytes.extend_from_slice(&self.geometric_altitude.to_le_bytes());
-        bytes.extend_from_slice(&self.ground_altitude.to_le_bytes());
+        bytes.extend_from_slice(&Self::encode_wire_altitude(format, self.pressure_altitude));
+        bytes.extend_from_slice(&Self::encode_wire_altitude(format, self.geometric_altitude));
+        bytes.extend_from_slice(&Self::encode_wire_altitude(format, self.ground_altitude));
         
         // 精度和时间戳
-        let accuracy_byte = (self.vertical_accuracy << 4) | (self.horizontal_accuracy & 0x0F);
+        let accuracy_byte = (u8::from(self.vertical_accuracy) << 4) | (u8::from(self.horizontal_accuracy) & 0x0F);
         bytes.push(accuracy_byte);
-        bytes.push(self.speed_accuracy & 0x0F);
+        bytes.push(u8::from(self.speed_accuracy) & 0x0F);
         bytes.extend_from_slice(&self.timestamp.to_le_bytes());
         
         // 最后2字节
-        bytes.push((self.timestamp_accuracy << 4) | (self.reserved & 0x0F));
+        bytes.push(self.timestamp_accuracy & 0x0F);
         bytes.push(self.reserved);
         
-        bytes
-    }
-
-    
-    fn print(&self) {
-        println!("=== PositionVectorMessage ===");
-        println!("运行状态: 0x{:X}", self.run_status);
-        println!("高度类型: {}", self.height_type);
-        println!("航迹方向: {}", if self.track_direction == 1 { "西" } else { "东" });
-        println!("航迹角: {}° (完整: {}°)", self.track_angle, self.calculate_full_track_angle());
-        println!("地速: {}节 (×{})", self.calculate_ground_speed_knots(), 
-                 if self.speed_multiplier == 1 { 10 } else { 1 });
-        println!("垂直速度: {} m/s", self.vertical_speed);
-        println!("位置: ({}, {})", 
-                 self.latitude , 
-                 self.longitude);
-        println!("高度: 气压={}m, 几何={}m, 距地={}m", 
-                 self.pressure_altitude, self.geometric_altitude, self.ground_altitude);
-        println!("精度: 垂直={}, 水平={}, 速度={}", 
-                 self.vertical_accuracy, self.horizontal_accuracy, self.speed_accuracy);
-        println!("时间戳: {} (0.1秒)", self.timestamp);
-        println!("时间精度: {}", self.timestamp_accuracy);
-        println!("预留: {:02X}", self.reserved);
+        Ok(bytes)
+    }
+
+    fn validate(&self) -> Result<(), MessageError> {
+        check_coded(self.run_status, 1)?;
+        check_coded(self.height_type, 1)?;
+        for (field, value) in [("track_direction", self.track_direction), ("speed_multiplier", self.speed_multiplier)] {
+            if value > 1 {
+                return Err(MessageError::FieldOutOfRange { field, offset: 1, value: value as i64, allowed: "0-1" });
+            }
+        }
+        if self.track_angle > 179 {
+            return Err(MessageError::FieldOutOfRange {
+                field: "track_angle", offset: 2, value: self.track_angle as i64, allowed: "0-179",
+            });
+        }
+        if self.latitude.abs() > 900_000_000 {
+            return Err(MessageError::FieldOutOfRange {
+                field: "latitude", offset: 5, value: self.latitude as i64, allowed: "-900000000-900000000",
+            });
+        }
+        if self.longitude.abs() > 1_800_000_000 {
+            return Err(MessageError::FieldOutOfRange {
+                field: "longitude", offset: 9, value: self.longitude as i64, allowed: "-1800000000-1800000000",
+            });
+        }
+        check_coded(self.vertical_accuracy, 19)?;
+        check_coded(self.horizontal_accuracy, 19)?;
+        check_coded(self.speed_accuracy, 20)?;
+        if self.timestamp as u32 > Self::MAX_TIMESTAMP && self.timestamp != u16::MAX {
+            return Err(MessageError::FieldOutOfRange {
+                field: "timestamp", offset: 21, value: self.timestamp as i64, allowed: "0-35999, 65535",
+            });
+        }
+        Ok(())
+    }
+
+    fn describe(&self) -> FieldNode {
+        self.describe_with(&WireFormat::default())
+    }
+
+    /// 高度的原始取值随编码风格变化，按给定格式计算
+    fn describe_with(&self, format: &WireFormat) -> FieldNode {
+        let raw_altitude = |altitude| u16::from_le_bytes(Self::encode_wire_altitude(format, altitude));
+        FieldNode::group("position_message", "位置向量报文")
+            .child(FieldNode::field("run_status", "运行状态", format!("{:?}", self.run_status)).raw(u8::from(self.run_status)))
+            .child(FieldNode::field("reserved_flag", "预留标志位", self.reserved_flag))
+            .child(FieldNode::field("height_type", "高度类型", format!("{:?}", self.height_type)).raw(u8::from(self.height_type)))
+            .child(FieldNode::field("track_direction", "航迹方向", if self.track_direction == 1 { "西" } else { "东" }).raw(self.track_direction))
+            .child(FieldNode::field("speed_multiplier", "速度乘数", self.speed_multiplier))
+            .child(FieldNode::field("track_angle", "航迹角", format!("{}°", self.track_deg())).raw(self.track_angle))
+            .child(FieldNode::field("ground_speed", "地速", format!("{:.2} m/s", self.ground_speed_mps())).raw(self.ground_speed))
+            .child(FieldNode::field("vertical_speed", "垂直速度", format!("{:.1} m/s", self.vertical_speed_mps())).raw(self.vertical_speed))
+            .child(FieldNode::field("latitude", "纬度", format!("{:.7}°", self.latitude_deg())).raw(self.latitude))
+            .child(FieldNode::field("longitude", "经度", format!("{:.7}°", self.longitude_deg())).raw(self.longitude))
+            .child(FieldNode::field("pressure_altitude", "气压高度", format!("{} m", self.pressure_altitude_m())).raw(raw_altitude(self.pressure_altitude)))
+            .child(FieldNode::field("geometric_altitude", "几何高度", format!("{} m", self.geometric_altitude_m())).raw(raw_altitude(self.geometric_altitude)))
+            .child(FieldNode::field("ground_altitude", "距地高度", format!("{} m", self.ground_altitude_m())).raw(raw_altitude(self.ground_altitude)))
+            .child(FieldNode::field("vertical_accuracy", "垂直精度", format!("{:?}", self.vertical_accuracy)).raw(u8::from(self.vertical_accuracy)))
+            .child(FieldNode::field("horizontal_accuracy", "水平精度", format!("{:?}", self.horizontal_accuracy)).raw(u8::from(self.horizontal_accuracy)))
+            .child(FieldNode::field("speed_accuracy", "速度精度", format!("{:?}", self.speed_accuracy)).raw(u8::from(self.speed_accuracy)))
+            .child(FieldNode::field("timestamp", "时间戳", format!("{:.1} s", self.timestamp as f32 / 10.0)).raw(self.timestamp))
+            .child(FieldNode::field("timestamp_accuracy", "时间精度", self.timestamp_accuracy))
+            .child(FieldNode::group("reserved", "预留").raw(self.reserved))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::protocol::{ProtocolVersion, WireFormat};
+    use proptest::prelude::*;
+
+    const SAMPLE_FRAME: &str = "11225a28fdc041c817c0f851450000780000005a0339300000";
+
+    fn sample() -> PositionVectorMessage {
+        let mut message = PositionVectorMessage::from_physical(39.9, 116.3, 120.0, 270.0, 10.0, -1.5).unwrap();
+        message.run_status = RunStatus::Airborne;
+        message.vertical_accuracy = VerticalAccuracy::LessThan3m;
+        message.horizontal_accuracy = HorizontalAccuracy::LessThan10m;
+        message.speed_accuracy = SpeedAccuracy::LessThan1mps;
+        message.timestamp = 12345;
+        message
+    }
+
+    fn raw_message() -> impl Strategy<Value = PositionVectorMessage> {
+        (
+            (0u8..16, any::<bool>(), 0u8..2, 0u8..2, 0u8..2),
+            (any::<u8>(), any::<u8>(), any::<i8>(), any::<i32>(), any::<i32>()),
+            (any::<i16>(), any::<i16>(), any::<i16>()),
+            (0u8..16, 0u8..16, 0u8..16, any::<u16>(), 0u8..16, any::<u8>()),
+        )
+            .prop_map(|(flags, motion, altitudes, tail)| PositionVectorMessage {
+                run_status: RunStatus::from_bits(flags.0),
+                reserved_flag: flags.1,
+                height_type: HeightType::from_bits(flags.2),
+                track_direction: flags.3,
+                speed_multiplier: flags.4,
+                track_angle: motion.0,
+                ground_speed: motion.1,
+                vertical_speed: motion.2,
+                latitude: motion.3,
+                longitude: motion.4,
+                pressure_altitude: altitudes.0 as f32,
+                geometric_altitude: altitudes.1 as f32,
+                ground_altitude: altitudes.2 as f32,
+                vertical_accuracy: VerticalAccuracy::from_bits(tail.0),
+                horizontal_accuracy: HorizontalAccuracy::from_bits(tail.1),
+                speed_accuracy: SpeedAccuracy::from_bits(tail.2),
+                timestamp: tail.3,
+                timestamp_accuracy: tail.4,
+                reserved: tail.5,
+            })
+    }
+
+    #[test]
+    fn encodes_sample_frame() {
+        assert_eq!(hex::encode(sample().encode().unwrap()), SAMPLE_FRAME);
+    }
+
+    #[test]
+    fn decodes_sample_frame() {
+        let frame = hex::decode(SAMPLE_FRAME).unwrap();
+        let message = PositionVectorMessage::from_bytes(&frame[1..]).unwrap();
+        assert_eq!(message, sample());
+        assert_eq!(message.track_deg(), 270);
+        assert_eq!(message.ground_speed_mps(), 10.0);
+        assert_eq!(message.vertical_speed_mps(), -1.5);
+    }
+
+    #[test]
+    fn byte0_fields_do_not_overlap() {
+        let message = PositionVectorMessage {
+            height_type: HeightType::AboveGroundLevel,
+            track_direction: 1,
+            ..Default::default()
+        };
+        let encoded = message.encode().unwrap();
+        assert_eq!(encoded[1], 0b0000_0110);
+        assert_eq!(PositionVectorMessage::from_bytes(&encoded[1..]).unwrap(), message);
+    }
+
+    #[test]
+    fn describes_raw_altitude_per_profile() {
+        let message = sample();
+        let description = message.describe();
+        assert_eq!(description.get("geometric_altitude").unwrap().raw.as_deref(), Some("0x78"));
+        assert_eq!(description.get("pressure_altitude").unwrap().raw.as_deref(), Some("0x0"));
+
+        let description = message.describe_with(&WireFormat::astm(ProtocolVersion::F3411_22a));
+        assert_eq!(description.get("geometric_altitude").unwrap().raw.as_deref(), Some("0x8C0"));
+        assert_eq!(description.get("pressure_altitude").unwrap().raw.as_deref(), Some("0x7D0"));
+    }
+
+    #[test]
+    fn rejects_single_bit_flags_above_one() {
+        let message = PositionVectorMessage { track_direction: 2, ..Default::default() };
+        assert_eq!(message.encode(), Err(MessageError::InvalidFieldValue("track_direction", 2)));
+        assert_eq!(
+            message.validate(),
+            Err(MessageError::FieldOutOfRange { field: "track_direction", offset: 1, value: 2, allowed: "0-1" })
+        );
+        let message = PositionVectorMessage { speed_multiplier: 3, ..Default::default() };
+        assert_eq!(message.encode(), Err(MessageError::InvalidFieldValue("speed_multiplier", 3)));
+        assert!(message.validate().is_err());
+    }
+
+    #[test]
+    fn ground_speed_switches_multiplier_at_boundary() {
+        let mut message = PositionVectorMessage::default();
+        message.set_ground_speed_mps(63.5).unwrap();
+        assert_eq!((message.speed_multiplier, message.ground_speed), (0, 254));
+        message.set_ground_speed_mps(64.5).unwrap();
+        assert_eq!((message.speed_multiplier, message.ground_speed), (1, 1));
+        message.set_ground_speed_mps(1000.0).unwrap();
+        assert_eq!((message.speed_multiplier, message.ground_speed), (1, 254));
+        assert!(message.set_ground_speed_mps(-0.1).is_err());
+    }
+
+    #[test]
+    fn vertical_speed_saturates() {
+        let mut message = PositionVectorMessage::default();
+        message.set_vertical_speed_mps(100.0).unwrap();
+        assert_eq!(message.vertical_speed, 124);
+        message.set_vertical_speed_mps(-100.0).unwrap();
+        assert_eq!(message.vertical_speed, -124);
+    }
+
+    #[test]
+    fn coordinates_are_bounded() {
+        let mut message = PositionVectorMessage::default();
+        assert!(message.set_latitude_deg(90.0).is_ok());
+        assert!(message.set_latitude_deg(90.000001).is_err());
+        assert!(message.set_longitude_deg(-180.0).is_ok());
+        assert!(message.set_longitude_deg(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn timestamp_limit_is_validated() {
+        let mut message = PositionVectorMessage { timestamp: 35999, ..Default::default() };
+        assert!(message.validate().is_ok());
+        message.timestamp = 36000;
+        assert!(matches!(
+            message.validate(),
+            Err(MessageError::FieldOutOfRange { field: "timestamp", offset: 21, .. })
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips(message in raw_message()) {
+            let encoded = message.encode().unwrap();
+            prop_assert_eq!(encoded.len(), 25);
+            prop_assert_eq!(PositionVectorMessage::from_bytes(&encoded[1..]).unwrap(), message);
+        }
+
+        #[test]
+        fn round_trips_astm_altitudes(message in raw_message(), altitudes in any::<[u16; 3]>()) {
+            // 覆盖 ASTM 的全部取值，含 0.5 米和负高度
+            let message = PositionVectorMessage {
+                pressure_altitude: altitudes[0] as f32 * 0.5 - 1000.0,
+                geometric_altitude: altitudes[1] as f32 * 0.5 - 1000.0,
+                ground_altitude: altitudes[2] as f32 * 0.5 - 1000.0,
+                ..message
+            };
+            let format = WireFormat::astm(ProtocolVersion::F3411_22a);
+            let encoded = message.encode_with(&format).unwrap();
+            prop_assert_eq!(&encoded[13..19], &[altitudes[0].to_le_bytes(), altitudes[1].to_le_bytes(), altitudes[2].to_le_bytes()].concat()[..]);
+            let decoded = PositionVectorMessage::from_bytes_with(&encoded[1..], &format).unwrap();
+            prop_assert_eq!(decoded.encode_with(&format).unwrap(), encoded);
+            prop_assert_eq!(decoded, message);
+        }
+
+        #[test]
+        fn physical_values_round_trip(
+            latitude in -90.0f64..=90.0,
+            longitude in -180.0f64..=180.0,
+            track in 0u16..360,
+            speed in 0.0f32..=63.5,
+        ) {
+            let message = PositionVectorMessage::from_physical(latitude, longitude, 0.0, track as f32, speed, 0.0).unwrap();
+            prop_assert!((message.latitude_deg() - latitude).abs() <= 0.5e-7);
+            prop_assert!((message.longitude_deg() - longitude).abs() <= 0.5e-7);
+            prop_assert_eq!(message.track_deg(), track);
+            prop_assert!((message.ground_speed_mps() - speed).abs() <= 0.125);
+        }
     }
 }
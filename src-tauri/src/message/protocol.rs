@@ -0,0 +1,146 @@
+use serde::{Serialize, Deserialize};
+
+use super::integrity::Integrity;
+use super::message::{MessageError, MessageType};
+
+/// 协议版本，写入每条消息类型/协议字节的低4位
+///
+/// 不同版本的字段布局差异：
+/// - `F3411_19`: 系统报文不含等级分类、UA类别/等级、控制站高度和时间戳，认证数据最多5页
+/// - `F3411_20`: 与 F3411-22a 布局相同，认证数据最多16页
+/// - `F3411_22a`: 完整布局，认证数据最多16页
+/// - `Gb42590`: 在 F3411-22a 布局基础上，系统报文第1字节最高位为坐标系类型
+///
+/// `F3411_20` 和 `Gb42590` 的版本号都是 0x1，解码时按编码风格区分
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtocolVersion {
+    F3411_19,
+    F3411_20,
+    F3411_22a,
+    #[default]
+    Gb42590,
+}
+
+impl ProtocolVersion {
+    /// 类型/协议字节低4位的取值
+    pub fn nibble(self) -> u8 {
+        match self {
+            ProtocolVersion::F3411_19 => 0x00,
+            ProtocolVersion::F3411_20 | ProtocolVersion::Gb42590 => 0x01,
+            ProtocolVersion::F3411_22a => 0x02,
+        }
+    }
+
+    /// 按编码风格解析版本号，0x1 在仿大疆布局中为 GB 42590，在 ASTM 布局中为 F3411-20
+    pub fn from_nibble(nibble: u8, profile: EncodingProfile) -> Result<Self, MessageError> {
+        match nibble {
+            0x00 => Ok(ProtocolVersion::F3411_19),
+            0x01 => Ok(match profile {
+                EncodingProfile::DjiCompat => ProtocolVersion::Gb42590,
+                EncodingProfile::AstmF3411 => ProtocolVersion::F3411_20,
+            }),
+            0x02 => Ok(ProtocolVersion::F3411_22a),
+            other => Err(MessageError::InvalidFieldValue("protocol_version", other)),
+        }
+    }
+
+    /// 系统报文是否包含等级分类、UA类别/等级、控制站高度和时间戳
+    pub fn has_system_classification(self) -> bool {
+        self != ProtocolVersion::F3411_19
+    }
+
+    /// 系统报文第1字节最高位是否为坐标系类型
+    pub fn has_coordinate_system(self) -> bool {
+        self == ProtocolVersion::Gb42590
+    }
+
+    /// 认证数据最多可分的页数
+    pub fn max_auth_pages(self) -> usize {
+        match self {
+            ProtocolVersion::F3411_19 => 5,
+            ProtocolVersion::F3411_20 | ProtocolVersion::F3411_22a | ProtocolVersion::Gb42590 => 16,
+        }
+    }
+}
+
+/// 编码风格，决定字段缩放和整包的包尾
+///
+/// | 项目 | `DjiCompat` | `AstmF3411` |
+/// |------|-------------|-------------|
+/// | 位置向量报文高度 | 米, 有符号 | 0.5 米, 偏移 -1000 米 |
+/// | 系统报文高度 | 0.1 米 | 0.5 米, 偏移 -1000 米 |
+/// | 系统/认证报文时间戳 | Unix 时间 | 自 2019-01-01 起的秒数 |
+/// | 坐标系类型位 | 按协议版本 | 预留 |
+/// | 整包包尾 | 校验值 (默认 CRC16 XMODEM) + 3字节预留 | 校验值 (默认无) |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncodingProfile {
+    /// 仿大疆的国标布局
+    #[default]
+    DjiCompat,
+    /// 标准 ASTM F3411 / ASD-STAN Open Drone ID 布局
+    AstmF3411,
+}
+
+/// 编解码时使用的线上格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WireFormat {
+    #[serde(default)]
+    pub version: ProtocolVersion,
+    #[serde(default)]
+    pub profile: EncodingProfile,
+    /// 整包校验算法，未指定时按编码风格取默认值
+    #[serde(default)]
+    pub integrity: Option<Integrity>,
+}
+
+impl WireFormat {
+    pub fn new(version: ProtocolVersion) -> Self {
+        Self { version, profile: EncodingProfile::default(), integrity: None }
+    }
+
+    /// 标准 ASTM F3411 线上格式
+    pub fn astm(version: ProtocolVersion) -> Self {
+        Self { version, profile: EncodingProfile::AstmF3411, integrity: None }
+    }
+
+    /// 实际使用的整包校验算法，仿大疆布局默认 CRC16 XMODEM，ASTM 默认不校验
+    pub fn integrity(&self) -> Integrity {
+        self.integrity.unwrap_or(match self.profile {
+            EncodingProfile::DjiCompat => Integrity::Crc16Xmodem,
+            EncodingProfile::AstmF3411 => Integrity::None,
+        })
+    }
+
+    pub fn is_astm(&self) -> bool {
+        self.profile == EncodingProfile::AstmF3411
+    }
+
+    /// 系统报文第1字节最高位是否为坐标系类型
+    pub fn has_coordinate_system(&self) -> bool {
+        !self.is_astm() && self.version.has_coordinate_system()
+    }
+
+    /// 消息首字节：高4位为消息类型，低4位为协议版本
+    pub fn header(&self, message_type: MessageType) -> u8 {
+        ((message_type as u8) << 4) | self.version.nibble()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_one_depends_on_profile() {
+        assert_eq!(ProtocolVersion::from_nibble(0x01, EncodingProfile::DjiCompat), Ok(ProtocolVersion::Gb42590));
+        assert_eq!(ProtocolVersion::from_nibble(0x01, EncodingProfile::AstmF3411), Ok(ProtocolVersion::F3411_20));
+        for profile in [EncodingProfile::DjiCompat, EncodingProfile::AstmF3411] {
+            assert_eq!(ProtocolVersion::from_nibble(0x00, profile), Ok(ProtocolVersion::F3411_19));
+            assert_eq!(ProtocolVersion::from_nibble(0x02, profile), Ok(ProtocolVersion::F3411_22a));
+            assert!(ProtocolVersion::from_nibble(0x03, profile).is_err());
+        }
+        assert_eq!(ProtocolVersion::F3411_20.nibble(), ProtocolVersion::Gb42590.nibble());
+    }
+}
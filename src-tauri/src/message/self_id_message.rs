@@ -0,0 +1,151 @@
+use std::str;
+use tracing::info;
+use serde::{Serialize, Deserialize};
+
+use crate::message::message::MessageType;
+
+use super::describe::FieldNode;
+use super::message::{Message, MessageError};
+use super::protocol::WireFormat;
+
+/// 自定义描述报文（报文类型 0x3），可选静态报文，用于描述飞行目的等文本信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelfIdMessage {
+    pub description_type: u8, // 描述类型 (0: 文本描述, 1: 紧急情况, 2: 扩展状态)
+    pub description: String,  // 描述文本 (最多23字节)
+}
+
+impl SelfIdMessage {
+    pub const MESSAGE_TYPE: u8 = 0x03;
+    const EXPECTED_LENGTH: usize = 24;
+    const DESCRIPTION_LENGTH: usize = 23;
+}
+
+impl Message for SelfIdMessage {
+    /// 从 u8 数组解析为结构化数据
+    ///
+    /// # 参数
+    /// - `data`: 至少包含 24 字节的输入数据
+    ///
+    /// # 错误
+    /// - 当输入数据长度不足时返回 ParseError::InsufficientLength
+    /// - 当描述文本不是有效的 UTF-8 时返回 ParseError::InvalidUtf8
+    fn from_bytes_with(data: &[u8], _format: &WireFormat) -> Result<Self, MessageError> {
+        if data.len() < Self::EXPECTED_LENGTH {
+            return Err(MessageError::InsufficientLength(
+                Self::EXPECTED_LENGTH,
+                data.len()
+            ));
+        }
+
+        // 解析描述类型 (起始字节 1)
+        let description_type = data[0];
+
+        // 解析描述文本 (起始字节 2，长度 23)
+        let description_bytes = &data[1..1 + Self::DESCRIPTION_LENGTH];
+        let description = match str::from_utf8(description_bytes) {
+            Ok(s) => s.trim_end_matches('\0').to_string(),
+            Err(e) => {
+                info!("self id message utf8 error.");
+                return Err(MessageError::InvalidUtf8(e))
+            }
+        };
+
+        Ok(Self {
+            description_type,
+            description,
+        })
+    }
+
+    fn encode_with(&self, format: &WireFormat) -> Result<Vec<u8>, MessageError> {
+        let mut bytes: Vec<u8> = Vec::new();
+
+        let message_protocol = format.header(MessageType::SelfIdMessageType);
+        bytes.push(message_protocol);
+        bytes.push(self.description_type);
+
+        // 编码描述文本（最多23字节），截断后发出的文本与调用方给出的不同，超长时拒绝编码
+        let description_bytes = self.description.as_bytes();
+        if description_bytes.len() > Self::DESCRIPTION_LENGTH {
+            return Err(MessageError::DataTooLong(Self::DESCRIPTION_LENGTH, description_bytes.len()));
+        }
+        let mut description = [0u8; Self::DESCRIPTION_LENGTH];
+        description[..description_bytes.len()].copy_from_slice(description_bytes);
+        bytes.extend_from_slice(&description);
+
+        Ok(bytes)
+    }
+
+    fn validate(&self) -> Result<(), MessageError> {
+        if self.description.len() > Self::DESCRIPTION_LENGTH {
+            return Err(MessageError::DataTooLong(Self::DESCRIPTION_LENGTH, self.description.len()));
+        }
+        match self.description_type {
+            0..=2 | 201..=255 => Ok(()),
+            value => Err(MessageError::FieldOutOfRange {
+                field: "description_type", offset: 1, value: value as i64, allowed: "0-2, 201-255",
+            }),
+        }
+    }
+
+    fn describe(&self) -> FieldNode {
+        FieldNode::group("self_id_message", "自定义描述报文")
+            .child(FieldNode::field("description_type", "描述类型", match self.description_type {
+                0 => "文本描述",
+                1 => "紧急情况",
+                2 => "扩展状态",
+                _ => "预留",
+            }).raw(self.description_type))
+            .child(FieldNode::field("description", "描述", &self.description).raw_bytes(self.description.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn message(description: &str) -> SelfIdMessage {
+        SelfIdMessage { description_type: 0, description: description.to_string() }
+    }
+
+    fn round_trip(message: &SelfIdMessage) -> SelfIdMessage {
+        let encoded = message.encode().unwrap();
+        assert_eq!(encoded.len(), 25);
+        SelfIdMessage::from_bytes(&encoded[1..]).unwrap()
+    }
+
+    #[test]
+    fn round_trips_chinese_text() {
+        let message = message("电力巡检");
+        assert_eq!(round_trip(&message), message);
+    }
+
+    #[test]
+    fn round_trips_at_length_limit() {
+        // 7 个汉字 21 字节 + 2 个 ASCII 字符，正好 23 字节
+        for description in ["ABCDEFGHIJKLMNOPQRSTUVW", "电力线路巡检中ab"] {
+            let message = message(description);
+            assert_eq!(message.description.len(), 23);
+            assert_eq!(message.validate(), Ok(()));
+            assert_eq!(round_trip(&message), message);
+        }
+    }
+
+    #[test]
+    fn rejects_overlong_description_instead_of_truncating() {
+        for (description, length) in [("ABCDEFGHIJKLMNOPQRSTUVWX", 24), ("航拍测绘与电力巡检作业", 33)] {
+            let message = message(description);
+            assert_eq!(message.encode(), Err(MessageError::DataTooLong(23, length)));
+            assert_eq!(message.validate(), Err(MessageError::DataTooLong(23, length)));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips(description_type in any::<u8>(), description in "[A-Za-z0-9 ]{0,11}[\u{4e00}-\u{9fa5}]{0,4}") {
+            let message = SelfIdMessage { description_type, description };
+            prop_assert_eq!(round_trip(&message), message);
+        }
+    }
+}
@@ -1,45 +1,50 @@
 use std::convert::TryInto;
-use tracing::info;
+use chrono::{DateTime, Utc};
+use tracing::trace;
 use serde::{Serialize, Deserialize};
+use super::field_types::{check_coded, ClassificationRegion, StationType};
+use super::bitfield::BitField;
+use super::describe::FieldNode;
 use super::message::{Message, MessageError, MessageType};
-use std::time::{SystemTime, UNIX_EPOCH};
+use super::protocol::WireFormat;
+use super::units::{ASTM_MIN_ALTITUDE, decode_astm_altitude, decode_astm_timestamp, decode_degrees, encode_astm_altitude, encode_astm_timestamp, encode_degrees, ensure_finite};
 
 // SystemMessage 结构体，系统报文（报文类型 0x4）为周期性，强制静态报文，用于描述无人驾驶航空器控制站位置和高度 、 航空器组群及额外的系统信息
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct SystemMessage {
     // 起始字节1 (1字节)
-    pub coordinate_system: u8,     // 坐标系类型 (7位)
+    pub coordinate_system: u8,     // 坐标系类型 (第7位)
     #[serde(default)]
     pub reserved_bits: u8,         // 预留位 (6-5位)
-    pub classification_region: u8, // 等级分类归属区域 (4-2位)
-    pub station_type: u8,          // 控制站位置类型 (1-0位)
+    pub classification_region: ClassificationRegion, // 等级分类归属区域 (4-2位)
+    pub station_type: StationType, // 控制站位置类型 (1-0位)
 
     // 起始字节2 (4字节)
-    pub latitude: i32,             // 控制站纬度 (小端序)
+    pub latitude: i32,             // 控制站纬度 (小端序, 1e-7 度)
 
     // 起始字节6 (4字节)
-    pub longitude: i32,             // 控制站经度 (小端序)
+    pub longitude: i32,             // 控制站经度 (小端序, 1e-7 度)
 
     // 可选字段
     pub operation_count: u16, // 运行区域计数 (小端序)
-    pub operation_radius: u8, // 运行区域半径 (*10)
-    pub altitude_upper: u16,  // 运行区域高度上限 (几何高度, 小端序)
-    pub altitude_lower: u16,  // 运行区域高度下限 (几何高度, 小端序)
+    pub operation_radius: u8, // 运行区域半径 (*10 米)
+    pub altitude_upper: i32,  // 运行区域高度上限 (几何高度, 0.1 米, 线上为2字节小端序)
+    pub altitude_lower: i32,  // 运行区域高度下限 (几何高度, 0.1 米, 线上为2字节小端序)
 
     // 起始字节17 (1字节)
     #[serde(default)]
-    pub ua_category: u8,           // UA运行类别
+    pub ua_category: u8,           // UA运行类别 (高4位)
 
-    // 起始字节18 (1字节)
+    // 起始字节17 (1字节)
     #[serde(default)]
-    pub ua_level: u8,              // UA等级
+    pub ua_level: u8,              // UA等级 (低4位)
 
-    // 起始字节19 (2字节)
+    // 起始字节18 (2字节)
     #[serde(default)]
-    pub station_altitude: u16,     // 控制站高度 (小端序)
+    pub station_altitude: i32,     // 控制站高度 (0.1 米, 线上为2字节小端序)
 
     // 时间戳
-    pub timestamp: u32,     // 时间戳 (Unix时间, 秒)
+    pub timestamp: u32,     // 时间戳 (Unix时间, 秒, ASTM 线上为自 2019-01-01 起的秒数)
     #[serde(default)]
     pub reserved: u8,       // 预留
 }
@@ -48,12 +53,213 @@ impl SystemMessage {
     pub const MESSAGE_TYPE: u8 = 0x04;
     const EXPECTED_LENGTH: usize = 24;
 
+    // 第1字节位段
+    const COORDINATE_SYSTEM: BitField = BitField::new(7, 1);
+    const RESERVED_BITS: BitField = BitField::new(5, 2);
+    const CLASSIFICATION_REGION: BitField = BitField::new(2, 3);
+    const STATION_TYPE: BitField = BitField::new(0, 2);
+    // 运行区域半径分辨率 (米)
+    const RADIUS_RESOLUTION: f32 = 10.0;
+    // 高度分辨率 (米)
+    const ALTITUDE_RESOLUTION: f32 = 0.1;
+
+    pub fn builder() -> SystemMessageBuilder {
+        SystemMessageBuilder::default()
+    }
+
+    /// 控制站纬度 (度)
+    pub fn operator_latitude_deg(&self) -> f64 {
+        decode_degrees(self.latitude)
+    }
+
+    /// 控制站经度 (度)
+    pub fn operator_longitude_deg(&self) -> f64 {
+        decode_degrees(self.longitude)
+    }
+
+    /// 运行区域半径 (米)
+    pub fn operation_radius_m(&self) -> f32 {
+        self.operation_radius as f32 * Self::RADIUS_RESOLUTION
+    }
+
+    /// 运行区域高度上限 (米)
+    pub fn altitude_upper_m(&self) -> f32 {
+        self.altitude_upper as f32 * Self::ALTITUDE_RESOLUTION
+    }
+
+    /// 运行区域高度下限 (米)
+    pub fn altitude_lower_m(&self) -> f32 {
+        self.altitude_lower as f32 * Self::ALTITUDE_RESOLUTION
+    }
+
+    /// 控制站高度 (米)
+    pub fn station_altitude_m(&self) -> f32 {
+        self.station_altitude as f32 * Self::ALTITUDE_RESOLUTION
+    }
+
+    /// 时间戳 (UTC)
+    pub fn timestamp_utc(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.timestamp as i64, 0)
+    }
+
+    /// 按 UTC 时间设置时间戳 (Unix时间, 秒)，超出 u32 范围时饱和
+    pub fn set_timestamp_utc(&mut self, time: DateTime<Utc>) {
+        self.timestamp = time.timestamp().clamp(0, u32::MAX as i64) as u32;
+    }
+
+    // 将米编码为 0.1 米单位，低于 ASTM 下限 (-1000 米) 时返回错误
+    fn encode_altitude(field: &'static str, altitude: f32) -> Result<i32, MessageError> {
+        ensure_finite(field, altitude as f64)?;
+        if altitude < ASTM_MIN_ALTITUDE {
+            return Err(MessageError::ValueOutOfRange(field, altitude as f64));
+        }
+        Ok((altitude / Self::ALTITUDE_RESOLUTION).round().min(i32::MAX as f32) as i32)
+    }
+
+    // 高度的线上编码，ASTM 为 0.5 米分辨率、偏移 -1000 米，否则为无符号 0.1 米，超出范围时饱和
+    fn encode_wire_altitude(format: &WireFormat, altitude: i32) -> [u8; 2] {
+        if format.is_astm() {
+            encode_astm_altitude(altitude as f32 * Self::ALTITUDE_RESOLUTION).to_le_bytes()
+        } else {
+            (altitude.clamp(0, u16::MAX as i32) as u16).to_le_bytes()
+        }
+    }
+
+    fn decode_wire_altitude(format: &WireFormat, bytes: [u8; 2]) -> i32 {
+        let value = u16::from_le_bytes(bytes);
+        if format.is_astm() {
+            (decode_astm_altitude(value) / Self::ALTITUDE_RESOLUTION).round() as i32
+        } else {
+            value as i32
+        }
+    }
+}
+
+/// 系统报文构造器，以度、米和 UTC 时间描述控制站和运行区域
+#[derive(Debug, Clone, Default)]
+pub struct SystemMessageBuilder {
+    coordinate_system: u8,
+    classification_region: ClassificationRegion,
+    station_type: StationType,
+    operator_latitude_deg: f64,
+    operator_longitude_deg: f64,
+    operation_count: u16,
+    operation_radius_m: f32,
+    altitude_upper_m: f32,
+    altitude_lower_m: f32,
+    ua_category: u8,
+    ua_level: u8,
+    station_altitude_m: f32,
+    timestamp: Option<DateTime<Utc>>,
 }
 
+impl SystemMessageBuilder {
+    pub fn coordinate_system(mut self, coordinate_system: u8) -> Self {
+        self.coordinate_system = coordinate_system;
+        self
+    }
+
+    pub fn classification_region(mut self, region: ClassificationRegion) -> Self {
+        self.classification_region = region;
+        self
+    }
+
+    pub fn station_type(mut self, station_type: StationType) -> Self {
+        self.station_type = station_type;
+        self
+    }
+
+    /// 控制站位置 (度)
+    pub fn operator_position(mut self, latitude_deg: f64, longitude_deg: f64) -> Self {
+        self.operator_latitude_deg = latitude_deg;
+        self.operator_longitude_deg = longitude_deg;
+        self
+    }
+
+    /// 控制站高度 (米)
+    pub fn station_altitude(mut self, altitude_m: f32) -> Self {
+        self.station_altitude_m = altitude_m;
+        self
+    }
+
+    /// 运行区域：区域计数和半径 (米)
+    pub fn operation_area(mut self, count: u16, radius_m: f32) -> Self {
+        self.operation_count = count;
+        self.operation_radius_m = radius_m;
+        self
+    }
+
+    /// 运行区域高度上下限 (米)
+    pub fn altitude_limits(mut self, lower_m: f32, upper_m: f32) -> Self {
+        self.altitude_lower_m = lower_m;
+        self.altitude_upper_m = upper_m;
+        self
+    }
+
+    /// UA运行类别和等级
+    pub fn ua_classification(mut self, category: u8, level: u8) -> Self {
+        self.ua_category = category;
+        self.ua_level = level;
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// # 错误
+    /// - 经纬度超出范围或数值非有限时返回 MessageError::ValueOutOfRange
+    /// - 半径、高度为负，或时间戳超出 u32 秒范围时返回 MessageError::ValueOutOfRange
+    ///
+    /// 半径和高度超出编码上限时饱和。
+    pub fn build(self) -> Result<SystemMessage, MessageError> {
+        ensure_finite("operation_radius", self.operation_radius_m as f64)?;
+        if self.operation_radius_m < 0.0 {
+            return Err(MessageError::ValueOutOfRange("operation_radius", self.operation_radius_m as f64));
+        }
+        let operation_radius = (self.operation_radius_m / SystemMessage::RADIUS_RESOLUTION)
+            .round()
+            .min(u8::MAX as f32) as u8;
+
+        let timestamp = match self.timestamp {
+            Some(time) => u32::try_from(time.timestamp())
+                .map_err(|_| MessageError::ValueOutOfRange("timestamp", time.timestamp() as f64))?,
+            None => 0,
+        };
+
+        Ok(SystemMessage {
+            coordinate_system: self.coordinate_system,
+            reserved_bits: 0,
+            classification_region: self.classification_region,
+            station_type: self.station_type,
+            latitude: encode_degrees("latitude", self.operator_latitude_deg, 90.0)?,
+            longitude: encode_degrees("longitude", self.operator_longitude_deg, 180.0)?,
+            operation_count: self.operation_count,
+            operation_radius,
+            altitude_upper: SystemMessage::encode_altitude("altitude_upper", self.altitude_upper_m)?,
+            altitude_lower: SystemMessage::encode_altitude("altitude_lower", self.altitude_lower_m)?,
+            ua_category: self.ua_category,
+            ua_level: self.ua_level,
+            station_altitude: SystemMessage::encode_altitude("station_altitude", self.station_altitude_m)?,
+            timestamp,
+            reserved: 0,
+        })
+    }
+}
+
+
+// 第1字节的位段不得重叠，编译期检查
+const _: () = BitField::assert_disjoint(&[
+    SystemMessage::COORDINATE_SYSTEM,
+    SystemMessage::RESERVED_BITS,
+    SystemMessage::CLASSIFICATION_REGION,
+    SystemMessage::STATION_TYPE,
+]);
 
 // 实现 Message trait
 impl Message for SystemMessage {
-    fn from_bytes(data: &[u8]) -> Result<Self, MessageError> {
+    fn from_bytes_with(data: &[u8], format: &WireFormat) -> Result<Self, MessageError> {
         
         if data.len() < Self::EXPECTED_LENGTH {
             return Err(MessageError::InsufficientLength(
@@ -64,17 +270,18 @@ impl Message for SystemMessage {
 
         // 解析起始字节1
         let byte0 = data[0];
-        let coordinate_system = (byte0 >> 5) & 0x07; // 取bit7-5
-        let reserved_bits = (byte0 >> 3) & 0x03;    // 取bit6-5
-        let classification_region = (byte0 >> 2) & 0x07; // 取bit4-2
+        let version = format.version;
+        // 坐标系类型仅 GB 42590 的仿大疆布局定义，其余情况该位为预留
+        let coordinate_system = if format.has_coordinate_system() { Self::COORDINATE_SYSTEM.get(byte0) } else { 0 };
+        let reserved_bits = Self::RESERVED_BITS.get(byte0);
+        let classification_region = if version.has_system_classification() {
+            ClassificationRegion::from_bits(Self::CLASSIFICATION_REGION.get(byte0))
+        } else {
+            ClassificationRegion::default()
+        };
+        trace!("class region = {:?}", classification_region);
         
-        // 验证分类区域值
-        if classification_region == 0 || classification_region > 3 {
-            info!("class region = {}", classification_region);
-            return Err(MessageError::UnknownMessageType(1));
-        }
-        
-        let station_type = byte0 & 0x03; // 取bit1-0
+        let station_type = StationType::from_bits(Self::STATION_TYPE.get(byte0));
 
         // 解析控制站纬度 (小端序)
         let latitude = i32::from_le_bytes(data[1..5].try_into()
@@ -94,31 +301,31 @@ impl Message for SystemMessage {
         let operation_radius = value;
         offset += 1;
 
-        let value = u16::from_le_bytes([data[offset], data[offset+1]]);
-        let altitude_upper = value;
+        let altitude_upper = Self::decode_wire_altitude(format, [data[offset], data[offset+1]]);
         offset += 2;
 
-        let value = u16::from_le_bytes([data[offset], data[offset+1]]);
-        let altitude_lower = value;
+        let altitude_lower = Self::decode_wire_altitude(format, [data[offset], data[offset+1]]);
         offset += 2;
 
-        // 解析必送字段
-        let ua_category = data[offset];
-        offset += 1;
-        
-        let ua_level = data[offset];
-        offset += 1;
-        
-        // 解析控制站高度
-        let station_altitude = u16::from_le_bytes([data[offset], data[offset+1]]);
-        offset += 2;
-   
+        // F3411-19 中以下字段均为预留，按默认值处理
+        let (ua_category, ua_level, station_altitude, timestamp) = if version.has_system_classification() {
+            // 解析必送字段，UA运行类别(高4位)和UA等级(低4位)共用1字节
+            let ua_category = (data[offset] >> 4) & 0x0F;
+            let ua_level = data[offset] & 0x0F;
+
+            // 解析控制站高度
+            let station_altitude = Self::decode_wire_altitude(format, [data[offset+1], data[offset+2]]);
 
-        // 时间及尾部字段
-        let timestamp = u32::from_le_bytes([
-            data[offset], data[offset+1], data[offset+2], data[offset+3]
-        ]);
-        offset += 4;
+            // 时间戳
+            let timestamp = u32::from_le_bytes([
+                data[offset+3], data[offset+4], data[offset+5], data[offset+6]
+            ]);
+            let timestamp = if format.is_astm() { decode_astm_timestamp(timestamp) } else { timestamp };
+            (ua_category, ua_level, station_altitude, timestamp)
+        } else {
+            (0, 0, 0, 0)
+        };
+        offset += 7;
 
         let reserved = data[offset];
 
@@ -141,19 +348,25 @@ impl Message for SystemMessage {
         })
     }
 
-    fn encode(&self) -> Vec<u8> {
+    fn encode_with(&self, format: &WireFormat) -> Result<Vec<u8>, MessageError> {
         let mut bytes = Vec::new();
         
-        let message_type = MessageType::SystemMessageType as u8;
-        let message_protocol = (message_type << 4) | 0x01;
+        let message_protocol = format.header(MessageType::SystemMessageType);
         bytes.push(message_protocol);
 
+        let version = format.version;
+
         // 第1字节编码
-        let mut byte1 = (self.coordinate_system & 0x7F) << 1;
-        byte1 |= (self.reserved_bits & 0x03) << 5;
-        byte1 |= (self.classification_region & 0x07) << 2;
-        byte1 |= self.station_type & 0x03;
-        bytes.push(byte1 as u8);
+        let mut byte1 = 0;
+        if format.has_coordinate_system() {
+            Self::COORDINATE_SYSTEM.set(&mut byte1, self.coordinate_system);
+        }
+        Self::RESERVED_BITS.set(&mut byte1, self.reserved_bits);
+        if version.has_system_classification() {
+            Self::CLASSIFICATION_REGION.set(&mut byte1, u8::from(self.classification_region));
+        }
+        Self::STATION_TYPE.set(&mut byte1, u8::from(self.station_type));
+        bytes.push(byte1);
         
         // 经纬度编码（小端序）
         bytes.extend_from_slice(&self.latitude.to_le_bytes());
@@ -162,52 +375,227 @@ impl Message for SystemMessage {
         // count and radius
         bytes.extend_from_slice(&self.operation_count.to_le_bytes());
         bytes.push(self.operation_radius);
-        bytes.extend_from_slice(&self.altitude_upper.to_le_bytes());
-        bytes.extend_from_slice(&self.altitude_lower.to_le_bytes());
-        
-        // UA类别和等级
-        let ua_category_level = self.ua_category << 4 | self.ua_level;
-        bytes.push(ua_category_level);
-        
-        // 控制站高度
-        bytes.extend_from_slice(&self.station_altitude.to_le_bytes());
+        bytes.extend_from_slice(&Self::encode_wire_altitude(format, self.altitude_upper));
+        bytes.extend_from_slice(&Self::encode_wire_altitude(format, self.altitude_lower));
         
-        // 时间戳和预留
-         let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as u32;
-        bytes.extend_from_slice(&timestamp.to_le_bytes());
+        if version.has_system_classification() {
+            // UA类别和等级
+            let ua_category_level = (self.ua_category << 4) | (self.ua_level & 0x0F);
+            bytes.push(ua_category_level);
+
+            // 控制站高度
+            bytes.extend_from_slice(&Self::encode_wire_altitude(format, self.station_altitude));
+
+            // 时间戳和预留，时间戳的取值由整包的 TimestampPolicy 决定
+            let timestamp = if format.is_astm() { encode_astm_timestamp(self.timestamp) } else { self.timestamp };
+            bytes.extend_from_slice(&timestamp.to_le_bytes());
+        } else {
+            // F3411-19 中为预留
+            bytes.extend_from_slice(&[0u8; 7]);
+        }
 
         bytes.push(self.reserved);
         
-        bytes
-    }
-
-    fn print(&self) {
-        println!("=== 系统消息 (SystemMessage) ===");
-        println!("坐标系类型: {}", self.coordinate_system);
-        println!("预留位: {:02b}", self.reserved_bits);
-        println!("等级分类归属区域: {}", match self.classification_region {
-            2 => "中国",
-            3..=7 => "预留",
-            _ => "未定义或无效",
-        });
-        println!("控制站位置类型: {}", self.station_type);
-        println!("控制站纬度: {:.6}°", self.latitude as f64 * 1e-7);
-        println!("控制站经度: {:.6}°", self.longitude as f64 * 1e-7);
-        
-        println!("运行区域计数: {}", self.operation_count);
-        println!("运行区域半径: {} (实际: {} 米)", self.operation_radius, self.operation_radius as f32 * 10.0);
-        
-        println!("运行区域高度上限: {} (实际: {:.1} 米)", self.altitude_upper, self.altitude_upper as f32 * 0.1);
-        println!("运行区域高度下限: {} (实际: {:.1} 米)", self.altitude_lower, self.altitude_lower as f32 * 0.1);
-        
-        println!("UA运行类别: {}", self.ua_category);
-        println!("UA等级: {}", self.ua_level);
-        println!("控制站高度: {} (实际: {:.1} 米)", self.station_altitude, self.station_altitude as f32 * 0.1);
-        
-        // 实际应用中可将时间戳转换为可读时间
-        println!("时间戳: {}", self.timestamp);
+        Ok(bytes)
+    }
 
-        println!("预留字段: {:02X}", self.reserved);
-        
+    fn validate(&self) -> Result<(), MessageError> {
+        if self.coordinate_system > 1 {
+            return Err(MessageError::FieldOutOfRange {
+                field: "coordinate_system", offset: 1, value: self.coordinate_system as i64, allowed: "0, 1",
+            });
+        }
+        check_coded(self.classification_region, 1)?;
+        check_coded(self.station_type, 1)?;
+        if self.latitude.abs() > 900_000_000 {
+            return Err(MessageError::FieldOutOfRange {
+                field: "latitude", offset: 2, value: self.latitude as i64, allowed: "-900000000-900000000",
+            });
+        }
+        if self.longitude.abs() > 1_800_000_000 {
+            return Err(MessageError::FieldOutOfRange {
+                field: "longitude", offset: 6, value: self.longitude as i64, allowed: "-1800000000-1800000000",
+            });
+        }
+        Ok(())
+    }
+
+    fn describe(&self) -> FieldNode {
+        self.describe_with(&WireFormat::default())
+    }
+
+    /// 高度的原始取值随编码风格变化，按给定格式计算
+    fn describe_with(&self, format: &WireFormat) -> FieldNode {
+        let raw_altitude = |altitude| u16::from_le_bytes(Self::encode_wire_altitude(format, altitude));
+        let timestamp = match self.timestamp_utc() {
+            Some(time) => time.to_string(),
+            None => "未知".to_string(),
+        };
+        FieldNode::group("system_message", "系统报文")
+            .child(FieldNode::field("coordinate_system", "坐标系类型", self.coordinate_system))
+            .child(FieldNode::group("reserved_bits", "预留位").raw(self.reserved_bits))
+            .child(FieldNode::field("classification_region", "等级分类归属区域", match self.classification_region {
+                ClassificationRegion::China => "中国",
+                ClassificationRegion::Unknown(3..=7) => "预留",
+                _ => "未定义或无效",
+            }).raw(u8::from(self.classification_region)))
+            .child(FieldNode::field("station_type", "控制站位置类型", format!("{:?}", self.station_type)).raw(u8::from(self.station_type)))
+            .child(FieldNode::field("latitude", "控制站纬度", format!("{:.7}°", self.operator_latitude_deg())).raw(self.latitude))
+            .child(FieldNode::field("longitude", "控制站经度", format!("{:.7}°", self.operator_longitude_deg())).raw(self.longitude))
+            .child(FieldNode::field("operation_count", "运行区域计数", self.operation_count))
+            .child(FieldNode::field("operation_radius", "运行区域半径", format!("{} m", self.operation_radius_m())).raw(self.operation_radius))
+            .child(FieldNode::field("altitude_upper", "运行区域高度上限", format!("{:.1} m", self.altitude_upper_m())).raw(raw_altitude(self.altitude_upper)))
+            .child(FieldNode::field("altitude_lower", "运行区域高度下限", format!("{:.1} m", self.altitude_lower_m())).raw(raw_altitude(self.altitude_lower)))
+            .child(FieldNode::field("ua_category", "UA运行类别", self.ua_category))
+            .child(FieldNode::field("ua_level", "UA等级", self.ua_level))
+            .child(FieldNode::field("station_altitude", "控制站高度", format!("{:.1} m", self.station_altitude_m())).raw(raw_altitude(self.station_altitude)))
+            .child(FieldNode::field("timestamp", "时间戳", timestamp).raw(self.timestamp))
+            .child(FieldNode::group("reserved", "预留字段").raw(self.reserved))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::protocol::{ProtocolVersion, WireFormat};
+    use chrono::TimeZone;
+    use proptest::prelude::*;
+
+    const SAMPLE_FRAME: &str = "4109c041c817c0f85145010032b004000012c7018085746700";
+
+    fn sample() -> SystemMessage {
+        SystemMessage::builder()
+            .classification_region(ClassificationRegion::China)
+            .station_type(StationType::LiveGnss)
+            .operator_position(39.9, 116.3)
+            .operation_area(1, 500.0)
+            .altitude_limits(0.0, 120.0)
+            .ua_classification(1, 2)
+            .station_altitude(45.5)
+            .timestamp(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap())
+            .build()
+            .unwrap()
+    }
+
+    fn raw_message() -> impl Strategy<Value = SystemMessage> {
+        (
+            (0u8..2, 0u8..4, 0u8..8, 0u8..4, any::<i32>(), any::<i32>()),
+            (any::<u16>(), any::<u8>(), any::<u16>(), any::<u16>()),
+            (0u8..16, 0u8..16, any::<u16>(), any::<u32>(), any::<u8>()),
+        )
+            .prop_map(|(head, area, tail)| SystemMessage {
+                coordinate_system: head.0,
+                reserved_bits: head.1,
+                classification_region: ClassificationRegion::from_bits(head.2),
+                station_type: StationType::from_bits(head.3),
+                latitude: head.4,
+                longitude: head.5,
+                operation_count: area.0,
+                operation_radius: area.1,
+                altitude_upper: area.2 as i32,
+                altitude_lower: area.3 as i32,
+                ua_category: tail.0,
+                ua_level: tail.1,
+                station_altitude: tail.2 as i32,
+                timestamp: tail.3,
+                reserved: tail.4,
+            })
+    }
+
+    #[test]
+    fn encodes_sample_frame() {
+        assert_eq!(hex::encode(sample().encode().unwrap()), SAMPLE_FRAME);
+    }
+
+    #[test]
+    fn decodes_sample_frame() {
+        let frame = hex::decode(SAMPLE_FRAME).unwrap();
+        let message = SystemMessage::from_bytes(&frame[1..]).unwrap();
+        assert_eq!(message, sample());
+        assert_eq!(message.operation_radius_m(), 500.0);
+        assert_eq!(message.station_altitude_m(), 45.5);
+    }
+
+    #[test]
+    fn byte0_fields_do_not_overlap() {
+        let message = SystemMessage { coordinate_system: 1, reserved_bits: 0b10, ..Default::default() };
+        let encoded = message.encode().unwrap();
+        assert_eq!(encoded[1], 0b1100_0000);
+        assert_eq!(SystemMessage::from_bytes(&encoded[1..]).unwrap(), message);
+    }
+
+    #[test]
+    fn rejects_altitude_below_astm_range() {
+        assert_eq!(
+            SystemMessage::builder().altitude_limits(-1000.5, 120.0).build(),
+            Err(MessageError::ValueOutOfRange("altitude_lower", -1000.5))
+        );
+        let message = SystemMessage::builder().altitude_limits(-1000.0, 120.0).build().unwrap();
+        assert_eq!(message.altitude_lower_m(), -1000.0);
+    }
+
+    #[test]
+    fn astm_keeps_negative_altitude() {
+        let message = SystemMessage::builder().altitude_limits(-12.5, 120.0).station_altitude(-0.5).build().unwrap();
+        let format = WireFormat::astm(ProtocolVersion::F3411_22a);
+        let encoded = message.encode_with(&format).unwrap();
+        let decoded = SystemMessage::from_bytes_with(&encoded[1..], &format).unwrap();
+        assert_eq!(decoded.altitude_lower_m(), -12.5);
+        assert_eq!(decoded.station_altitude_m(), -0.5);
+        // 仿大疆布局为无符号，负高度按 0 编码
+        let encoded = message.encode().unwrap();
+        assert_eq!(SystemMessage::from_bytes(&encoded[1..]).unwrap().altitude_lower, 0);
+    }
+
+    #[test]
+    fn describes_raw_altitude_per_profile() {
+        let message = sample();
+        let description = message.describe();
+        assert_eq!(description.get("altitude_upper").unwrap().raw.as_deref(), Some("0x4B0"));
+        assert_eq!(description.get("station_altitude").unwrap().raw.as_deref(), Some("0x1C7"));
+
+        let description = message.describe_with(&WireFormat::astm(ProtocolVersion::F3411_22a));
+        assert_eq!(description.get("altitude_upper").unwrap().raw.as_deref(), Some("0x8C0"));
+        assert_eq!(description.get("altitude_lower").unwrap().raw.as_deref(), Some("0x7D0"));
+        assert_eq!(description.get("station_altitude").unwrap().raw.as_deref(), Some("0x82B"));
+    }
+
+    #[test]
+    fn f3411_19_omits_classification_fields() {
+        let format = WireFormat::new(ProtocolVersion::F3411_19);
+        let encoded = sample().encode_with(&format).unwrap();
+        assert_eq!(encoded[0], 0x40);
+        assert_eq!(encoded[1], 0x01);
+        assert!(encoded[17..24].iter().all(|&byte| byte == 0));
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips(message in raw_message()) {
+            let encoded = message.encode().unwrap();
+            prop_assert_eq!(encoded.len(), 25);
+            prop_assert_eq!(SystemMessage::from_bytes(&encoded[1..]).unwrap(), message);
+        }
+
+        #[test]
+        fn round_trips_astm_altitudes(message in raw_message(), altitudes in any::<[u16; 3]>()) {
+            // 覆盖 ASTM 的全部取值，含 0.5 米和负高度
+            let message = SystemMessage {
+                altitude_upper: altitudes[0] as i32 * 5 - 10000,
+                altitude_lower: altitudes[1] as i32 * 5 - 10000,
+                station_altitude: altitudes[2] as i32 * 5 - 10000,
+                ..message
+            };
+            let format = WireFormat::astm(ProtocolVersion::F3411_22a);
+            let encoded = message.encode_with(&format).unwrap();
+            prop_assert_eq!(&encoded[13..17], &[altitudes[0].to_le_bytes(), altitudes[1].to_le_bytes()].concat()[..]);
+            prop_assert_eq!(&encoded[18..20], &altitudes[2].to_le_bytes()[..]);
+            let decoded = SystemMessage::from_bytes_with(&encoded[1..], &format).unwrap();
+            prop_assert_eq!(decoded.encode_with(&format).unwrap(), encoded);
+            prop_assert_eq!(
+                (decoded.altitude_upper, decoded.altitude_lower, decoded.station_altitude),
+                (message.altitude_upper, message.altitude_lower, message.station_altitude)
+            );
+        }
     }
 }
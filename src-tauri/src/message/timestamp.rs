@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+
+/// 编码时报文时间戳的取值策略，同时作用于位置向量报文和系统报文
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampPolicy {
+    /// 使用报文中已有的时间戳，回放时保证逐字节一致
+    Provided,
+    /// 使用系统当前时间
+    #[default]
+    WallClock,
+    /// 使用模拟时钟，由调用方推进
+    #[serde(skip)]
+    Simulated(SimulatedClock),
+}
+
+impl TimestampPolicy {
+    /// 本次编码应写入的时间，`Provided` 时返回 None
+    pub fn now(&self) -> Option<DateTime<Utc>> {
+        match self {
+            TimestampPolicy::Provided => None,
+            TimestampPolicy::WallClock => Some(Utc::now()),
+            TimestampPolicy::Simulated(clock) => Some(clock.now()),
+        }
+    }
+}
+
+/// 手动推进的模拟时钟，克隆后共享同一时间
+#[derive(Debug, Clone)]
+pub struct SimulatedClock {
+    micros: Arc<AtomicI64>,
+}
+
+impl SimulatedClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            micros: Arc::new(AtomicI64::new(start.timestamp_micros())),
+        }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_micros(self.micros.load(Ordering::SeqCst))
+            .unwrap_or_default()
+    }
+
+    pub fn set(&self, time: DateTime<Utc>) {
+        self.micros.store(time.timestamp_micros(), Ordering::SeqCst);
+    }
+
+    /// 推进时钟，结果超出可表示的时间范围时停在范围边界
+    pub fn advance(&self, step: Duration) {
+        let step = step
+            .num_microseconds()
+            .unwrap_or(if step < Duration::zero() { i64::MIN } else { i64::MAX });
+        let min = DateTime::<Utc>::MIN_UTC.timestamp_micros();
+        let max = DateTime::<Utc>::MAX_UTC.timestamp_micros();
+        let _ = self.micros.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |micros| {
+            Some(micros.saturating_add(step).clamp(min, max))
+        });
+    }
+}
+
+impl PartialEq for SimulatedClock {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.micros, &other.micros)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn advance_saturates_at_time_range() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let clock = SimulatedClock::new(start);
+        clock.advance(Duration::milliseconds(100));
+        assert_eq!(clock.now(), start + Duration::milliseconds(100));
+
+        // 时钟精度为微秒
+        let max = DateTime::<Utc>::MAX_UTC.timestamp_micros();
+        clock.advance(Duration::MAX);
+        assert_eq!(clock.now().timestamp_micros(), max);
+        clock.advance(Duration::seconds(1));
+        assert_eq!(clock.now().timestamp_micros(), max);
+
+        clock.set(start);
+        clock.advance(Duration::MIN);
+        assert_eq!(clock.now().timestamp_micros(), DateTime::<Utc>::MIN_UTC.timestamp_micros());
+    }
+}
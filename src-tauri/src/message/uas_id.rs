@@ -0,0 +1,235 @@
+use std::collections::HashSet;
+use rand::Rng;
+
+use super::field_types::IdType;
+use super::message::MessageError;
+
+/// UAS ID 字段长度 (字节)
+pub const MAX_UAS_ID_LENGTH: usize = 20;
+
+// ANSI/CTA-2063-A 序列号可用字符，不含易混淆的 I 和 O
+const SERIAL_ALPHABET: &[u8] = b"0123456789ABCDEFGHJKLMNPQRSTUVWXYZ";
+// 长度码 1-F 对应序列号部分的长度 1-15
+const LENGTH_CODES: &[u8] = b"123456789ABCDEF";
+// 制造商代码长度
+const MANUFACTURER_CODE_LENGTH: usize = 4;
+// UUID 的二进制长度
+const UUID_LENGTH: usize = 16;
+
+/// 按 ID 类型校验 UAS ID
+///
+/// - `SerialNumber`: ANSI/CTA-2063-A，4 位制造商代码 + 1 位长度码 + 与长度码一致的序列号
+/// - `CaaRegistration`: 大写字母、数字、`.` 和 `-`，不超过 20 字节
+/// - `UtmAssigned`: RFC 4122 UUID 文本，线上以 16 字节二进制编码
+/// - `SpecificSession`: 可打印 ASCII，不超过 20 字节
+/// - 其余类型只检查长度
+pub fn validate_uas_id(id_type: IdType, uas_id: &str) -> Result<(), MessageError> {
+    match id_type {
+        IdType::SerialNumber => validate_serial_number(uas_id),
+        IdType::CaaRegistration => validate_caa_registration(uas_id),
+        IdType::UtmAssigned => parse_uuid(uas_id)
+            .map(|_| ())
+            .ok_or_else(|| invalid(uas_id, "UUID 格式应为 xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx")),
+        IdType::SpecificSession => validate_session_id(uas_id),
+        IdType::None | IdType::Unknown(_) => check_length(uas_id),
+    }
+}
+
+/// 校验 ANSI/CTA-2063-A 序列号
+pub fn validate_serial_number(serial: &str) -> Result<(), MessageError> {
+    check_length(serial)?;
+    let bytes = serial.as_bytes();
+    if bytes.len() < MANUFACTURER_CODE_LENGTH + 2 {
+        return Err(invalid(serial, "序列号过短"));
+    }
+    if !bytes.iter().all(|byte| SERIAL_ALPHABET.contains(byte)) {
+        return Err(invalid(serial, "只允许数字和除 I、O 外的大写字母"));
+    }
+
+    let length_code = bytes[MANUFACTURER_CODE_LENGTH];
+    let serial_length = LENGTH_CODES
+        .iter()
+        .position(|&code| code == length_code)
+        .map(|index| index + 1)
+        .ok_or_else(|| invalid(serial, "长度码应为 1-F"))?;
+    if bytes.len() - MANUFACTURER_CODE_LENGTH - 1 != serial_length {
+        return Err(invalid(serial, "序列号长度与长度码不一致"));
+    }
+    Ok(())
+}
+
+fn validate_caa_registration(registration: &str) -> Result<(), MessageError> {
+    check_length(registration)?;
+    if registration.is_empty() {
+        return Err(invalid(registration, "登记号不能为空"));
+    }
+    if !registration.bytes().all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit() || byte == b'.' || byte == b'-') {
+        return Err(invalid(registration, "只允许大写字母、数字、'.' 和 '-'"));
+    }
+    Ok(())
+}
+
+fn validate_session_id(session_id: &str) -> Result<(), MessageError> {
+    check_length(session_id)?;
+    if session_id.is_empty() {
+        return Err(invalid(session_id, "会话 ID 不能为空"));
+    }
+    if !session_id.bytes().all(|byte| byte.is_ascii_graphic()) {
+        return Err(invalid(session_id, "只允许可打印 ASCII 字符"));
+    }
+    Ok(())
+}
+
+fn check_length(uas_id: &str) -> Result<(), MessageError> {
+    if uas_id.len() > MAX_UAS_ID_LENGTH {
+        return Err(MessageError::DataTooLong(MAX_UAS_ID_LENGTH, uas_id.len()));
+    }
+    Ok(())
+}
+
+fn invalid(uas_id: &str, reason: &'static str) -> MessageError {
+    MessageError::InvalidUasId(uas_id.to_string(), reason)
+}
+
+/// 将 UUID 文本解析为 16 字节，连字符可省略
+pub(crate) fn parse_uuid(text: &str) -> Option<[u8; UUID_LENGTH]> {
+    let digits: String = text.chars().filter(|&c| c != '-').collect();
+    if digits.len() != UUID_LENGTH * 2 {
+        return None;
+    }
+    hex::decode(digits).ok()?.try_into().ok()
+}
+
+/// 将 16 字节格式化为带连字符的小写 UUID 文本
+pub(crate) fn format_uuid(bytes: &[u8; UUID_LENGTH]) -> String {
+    let digits = hex::encode(bytes);
+    format!("{}-{}-{}-{}-{}", &digits[0..8], &digits[8..12], &digits[12..16], &digits[16..20], &digits[20..32])
+}
+
+/// 按 ANSI/CTA-2063-A 生成随机序列号，用于机群场景
+#[derive(Debug, Clone)]
+pub struct SerialNumberGenerator {
+    prefix: String, // 制造商代码 + 长度码
+    serial_length: usize,
+}
+
+impl SerialNumberGenerator {
+    /// # 参数
+    /// - `manufacturer_code`: 4 位制造商代码
+    /// - `serial_length`: 序列号部分的长度 (1-15)
+    ///
+    /// # 错误
+    /// 制造商代码不合规时返回 MessageError::InvalidUasId，长度超出 1-15 时返回
+    /// MessageError::ValueOutOfRange
+    pub fn new(manufacturer_code: &str, serial_length: usize) -> Result<Self, MessageError> {
+        if manufacturer_code.len() != MANUFACTURER_CODE_LENGTH
+            || !manufacturer_code.bytes().all(|byte| SERIAL_ALPHABET.contains(&byte))
+        {
+            return Err(invalid(manufacturer_code, "制造商代码应为 4 位数字或除 I、O 外的大写字母"));
+        }
+        let length_code = match serial_length {
+            1..=15 => LENGTH_CODES[serial_length - 1] as char,
+            _ => return Err(MessageError::ValueOutOfRange("serial_length", serial_length as f64)),
+        };
+        Ok(Self {
+            prefix: format!("{}{}", manufacturer_code, length_code),
+            serial_length,
+        })
+    }
+
+    /// 生成一个序列号
+    pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let mut serial = self.prefix.clone();
+        serial.extend((0..self.serial_length).map(|_| {
+            SERIAL_ALPHABET[rng.gen_range(0..SERIAL_ALPHABET.len())] as char
+        }));
+        serial
+    }
+
+    /// 生成 `count` 个互不相同的序列号
+    ///
+    /// # 错误
+    /// `count` 超过该长度下可用序列号的数量时返回 MessageError::ValueOutOfRange
+    pub fn generate_unique<R: Rng + ?Sized>(&self, count: usize, rng: &mut R) -> Result<Vec<String>, MessageError> {
+        let capacity = (SERIAL_ALPHABET.len() as f64).powi(self.serial_length as i32);
+        if count as f64 > capacity {
+            return Err(MessageError::ValueOutOfRange("count", count as f64));
+        }
+
+        let mut seen = HashSet::with_capacity(count);
+        let mut serials = Vec::with_capacity(count);
+        while serials.len() < count {
+            let serial = self.generate(rng);
+            if seen.insert(serial.clone()) {
+                serials.push(serial);
+            }
+        }
+        Ok(serials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn accepts_valid_serial_numbers() {
+        assert!(validate_serial_number("1581F5FKD2294000A1B2").is_ok());
+        assert!(validate_serial_number("MFR011").is_ok());
+    }
+
+    #[test]
+    fn rejects_malformed_serial_numbers() {
+        // 长度码与实际长度不一致
+        assert!(validate_serial_number("1581F5FKD229400").is_err());
+        // 含有 O
+        assert!(validate_serial_number("MFRO1A").is_err());
+        // 长度码 0 无效
+        assert!(validate_serial_number("MFR10").is_err());
+        // 小写字母
+        assert!(validate_serial_number("mfr011").is_err());
+        assert_eq!(
+            validate_serial_number("1581F5FKD2294000A1B2C"),
+            Err(MessageError::DataTooLong(20, 21))
+        );
+    }
+
+    #[test]
+    fn validates_by_id_type() {
+        assert!(validate_uas_id(IdType::CaaRegistration, "CHN.UAS-0001").is_ok());
+        assert!(validate_uas_id(IdType::CaaRegistration, "chn 0001").is_err());
+        assert!(validate_uas_id(IdType::UtmAssigned, "123e4567-e89b-12d3-a456-426614174000").is_ok());
+        assert!(validate_uas_id(IdType::UtmAssigned, "123e4567").is_err());
+        assert!(validate_uas_id(IdType::SpecificSession, "SESSION-42").is_ok());
+        assert!(validate_uas_id(IdType::SpecificSession, "").is_err());
+        assert!(validate_uas_id(IdType::None, "").is_ok());
+    }
+
+    #[test]
+    fn uuid_text_round_trips() {
+        let text = "123e4567-e89b-12d3-a456-426614174000";
+        assert_eq!(format_uuid(&parse_uuid(text).unwrap()), text);
+    }
+
+    #[test]
+    fn generator_produces_valid_unique_serials() {
+        let generator = SerialNumberGenerator::new("1581", 15).unwrap();
+        let mut rng = StdRng::seed_from_u64(42);
+        let serials = generator.generate_unique(200, &mut rng).unwrap();
+        assert_eq!(serials.iter().collect::<HashSet<_>>().len(), 200);
+        for serial in &serials {
+            assert!(validate_serial_number(serial).is_ok(), "{}", serial);
+        }
+    }
+
+    #[test]
+    fn generator_rejects_bad_parameters() {
+        assert!(SerialNumberGenerator::new("15O1", 4).is_err());
+        assert_eq!(SerialNumberGenerator::new("1581", 0).err(), Some(MessageError::ValueOutOfRange("serial_length", 0.0)));
+        assert_eq!(SerialNumberGenerator::new("1581", 16).err(), Some(MessageError::ValueOutOfRange("serial_length", 16.0)));
+        let generator = SerialNumberGenerator::new("1581", 1).unwrap();
+        assert!(generator.generate_unique(35, &mut StdRng::seed_from_u64(1)).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use super::message::MessageError;
+
+// 经纬度分辨率 (1e-7 度)
+pub(crate) const LAT_LON_SCALE: f64 = 1e7;
+
+/// 检查物理量是否为有限值
+pub(crate) fn ensure_finite(field: &'static str, value: f64) -> Result<(), MessageError> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(MessageError::ValueOutOfRange(field, value))
+    }
+}
+
+/// 将角度编码为 1e-7 度，超出 ±limit 时返回错误
+pub(crate) fn encode_degrees(field: &'static str, degrees: f64, limit: f64) -> Result<i32, MessageError> {
+    ensure_finite(field, degrees)?;
+    if degrees.abs() > limit {
+        return Err(MessageError::ValueOutOfRange(field, degrees));
+    }
+    Ok((degrees * LAT_LON_SCALE).round() as i32)
+}
+
+/// 将 1e-7 度解码为角度
+pub(crate) fn decode_degrees(value: i32) -> f64 {
+    value as f64 / LAT_LON_SCALE
+}
+
+// ASTM F3411 高度编码: 0.5 米分辨率, 偏移 -1000 米
+const ASTM_ALTITUDE_RESOLUTION: f32 = 0.5;
+const ASTM_ALTITUDE_OFFSET: f32 = 1000.0;
+/// ASTM F3411 可编码的最低高度 (米)
+pub(crate) const ASTM_MIN_ALTITUDE: f32 = -ASTM_ALTITUDE_OFFSET;
+// ASTM F3411 时间戳起点 2019-01-01T00:00:00Z 的 Unix 时间
+const ASTM_EPOCH: u32 = 1_546_300_800;
+
+/// 将米编码为 ASTM F3411 高度，超出编码范围时饱和
+pub(crate) fn encode_astm_altitude(altitude: f32) -> u16 {
+    ((altitude + ASTM_ALTITUDE_OFFSET) / ASTM_ALTITUDE_RESOLUTION)
+        .round()
+        .clamp(0.0, u16::MAX as f32) as u16
+}
+
+/// 将 ASTM F3411 高度解码为米
+pub(crate) fn decode_astm_altitude(value: u16) -> f32 {
+    value as f32 * ASTM_ALTITUDE_RESOLUTION - ASTM_ALTITUDE_OFFSET
+}
+
+/// 将 Unix 时间 (秒) 换算为自 2019-01-01 起的秒数，0 表示未知
+pub(crate) fn encode_astm_timestamp(unix_seconds: u32) -> u32 {
+    unix_seconds.saturating_sub(ASTM_EPOCH)
+}
+
+/// 将自 2019-01-01 起的秒数换算为 Unix 时间 (秒)，0 表示未知
+pub(crate) fn decode_astm_timestamp(value: u32) -> u32 {
+    if value == 0 {
+        0
+    } else {
+        value.saturating_add(ASTM_EPOCH)
+    }
+}
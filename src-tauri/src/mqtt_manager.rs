@@ -4,18 +4,24 @@ use tokio::task::JoinHandle;
 use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, QoS, Packet, Publish};
 use tauri::{AppHandle, Emitter};
 use once_cell::sync::OnceCell;
-use tracing::{info, error};
+use tracing::{debug, info, error};
 
-use crate::message::packet_message::PacketMessage;
+use crate::message::describe::FieldNode;
+use crate::message::packet_message::PacketSequence;
 use crate::message::message::Message;
 use crate::rid_simulator::RidSimulator;
+use crate::config::parse_selectors;
+use crate::transport::{FanoutMode, InterfaceSelector, PcapngWriter, TransportStats};
+use crate::transport::interfaces::find_interface;
 
 #[derive(Debug, Clone)]
 pub struct MqttManager {
     client: Arc<Mutex<Option<AsyncClient>>>,
     event_loop_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
     connection_status: Arc<Mutex<bool>>,
-    rid_simulator: Arc<Mutex<Option<Arc<Mutex<RidSimulator>>>>>,
+    rid_simulator: Arc<Mutex<Option<Arc<RidSimulator>>>>, // 模拟器内部自行加锁，使用时克隆出 Arc 后释放此锁
+    interface_selectors: Arc<Mutex<Vec<InterfaceSelector>>>, // 界面上选择的网卡，优先于环境变量和配置文件
+    fanout_mode: Arc<Mutex<Option<FanoutMode>>>,             // 界面上选择的分发方式，优先于环境变量和配置文件
     app_handle: Arc<Mutex<Option<AppHandle>>>,
 }
 
@@ -26,6 +32,8 @@ impl MqttManager {
             event_loop_handle: Arc::new(Mutex::new(None)),
             connection_status: Arc::new(Mutex::new(false)),
             rid_simulator: Arc::new(Mutex::new(None)),
+            interface_selectors: Arc::new(Mutex::new(Vec::new())),
+            fanout_mode: Arc::new(Mutex::new(None)),
             app_handle: Arc::new(Mutex::new(None)),
         }
     }
@@ -43,11 +51,23 @@ impl MqttManager {
 
         // Initialize RidSimulator if not exists
         {
+            // 先取出界面上的设置，持有模拟器的锁时不再等待其他锁
+            let selectors = self.interface_selectors.lock().await.clone();
+            let fanout_mode = *self.fanout_mode.lock().await;
             let mut sim_guard = self.rid_simulator.lock().await;
             if sim_guard.is_none() {
-                let mut simulator = RidSimulator::new();
-                simulator.start_simulator();
-                *sim_guard = Some(Arc::new(Mutex::new(simulator)));
+                let simulator = RidSimulator::new();
+                // 设置了 RID_PCAPNG_PATH 时写入 pcapng 文件，不需要 WiFi 网卡
+                if let Some(writer) = PcapngWriter::from_env().transpose()? {
+                    simulator.set_transport(Box::new(writer));
+                } else if !selectors.is_empty() {
+                    simulator.select_interfaces(&selectors)?;
+                }
+                simulator.start_simulator()?;
+                if let Some(mode) = fanout_mode {
+                    simulator.set_fanout_mode(mode);
+                }
+                *sim_guard = Some(Arc::new(simulator));
                 info!("RidSimulator initialized");
             }
         }
@@ -133,6 +153,44 @@ impl MqttManager {
         *status
     }
 
+    /// 选择注入用的网卡 (逗号分隔)，模拟器已启动时立即切换，否则在下次连接时使用
+    pub async fn select_interfaces(&self, selectors: String) -> Result<Vec<String>, String> {
+        let selectors = parse_selectors(&selectors)?;
+        let descriptions = match self.simulator().await {
+            Some(simulator) => simulator.select_interfaces(&selectors)?,
+            None => selectors
+                .iter()
+                .map(|selector| find_interface(selector).map(|interface| interface.name))
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        info!("interfaces selected: {}", descriptions.join(", "));
+        *self.interface_selectors.lock().await = selectors;
+        Ok(descriptions)
+    }
+
+    /// 设置多块网卡时帧的分发方式，模拟器已启动时立即生效
+    pub async fn set_fanout_mode(&self, mode: String) -> Result<String, String> {
+        let mode: FanoutMode = mode.parse()?;
+        if let Some(simulator) = self.simulator().await {
+            simulator.set_fanout_mode(mode);
+        }
+        *self.fanout_mode.lock().await = Some(mode);
+        Ok(mode.to_string())
+    }
+
+    /// 各发送通道的统计，模拟器未启动时为空
+    pub async fn transport_stats(&self) -> Vec<TransportStats> {
+        match self.simulator().await {
+            Some(simulator) => simulator.transport_stats(),
+            None => Vec::new(),
+        }
+    }
+
+    /// 取出模拟器的共享引用，返回前释放外层的锁
+    async fn simulator(&self) -> Option<Arc<RidSimulator>> {
+        self.rid_simulator.lock().await.clone()
+    }
+
     async fn start_event_loop(&self, mut eventloop: EventLoop) -> JoinHandle<()> {
         let connection_status = self.connection_status.clone();
         let rid_simulator = self.rid_simulator.clone();
@@ -202,7 +260,7 @@ impl MqttManager {
 
     async fn handle_publish_packet(
         publish: Publish,
-        rid_simulator: Arc<Mutex<Option<Arc<Mutex<RidSimulator>>>>>,
+        rid_simulator: Arc<Mutex<Option<Arc<RidSimulator>>>>,
         app_handle: Arc<Mutex<Option<AppHandle>>>,
     ) {
         let topic = publish.topic;
@@ -214,30 +272,48 @@ impl MqttManager {
             &format!("收到MQTT消息: 主题={}, 大小={}字节", topic, payload.len()),
         ).await;
 
-        match serde_json::from_slice::<PacketMessage>(&payload) {
-            Ok(message) => {
+        match serde_json::from_slice::<PacketSequence>(&payload) {
+            Ok(sequence) => {
                 info!("Successfully parsed PacketMessage from JSON");
                 Self::send_log_to_frontend(app_handle.clone(), "成功解析PacketMessage数据").await;
 
-                if let Some(sim_arc) = rid_simulator.lock().await.as_ref() {
-                    let simulator = sim_arc.lock().await;
-                    let ssid = message.get_ssid();
-                    let encoded_data = message.encode();
-
-                    match simulator.build_and_send_rid(&ssid, encoded_data) {
-                        Ok(_) => {
-                            info!("Successfully sent RID for SSID: {}", ssid);
-                            Self::send_log_to_frontend(
-                                app_handle.clone(),
-                                &format!("成功发送RID数据包: SSID={}", ssid),
-                            ).await;
-                        }
-                        Err(e) => {
-                            error!("Failed to send RID: {}", e);
-                            Self::send_log_to_frontend(
-                                app_handle.clone(),
-                                &format!("发送RID失败: {}", e),
-                            ).await;
+                // 克隆出模拟器后立即释放外层的锁，编码和入队等待期间不持有任何锁
+                let simulator = rid_simulator.lock().await.clone();
+                if let Some(simulator) = simulator {
+                    // 认证页一包放不下时拆成连续多包，按顺序发送
+                    for mut message in sequence.into_packs() {
+                        let ssid = message.get_ssid();
+                        message.assign_next_counter();
+                        let encoded_data = match message.encode() {
+                            Ok(data) => data,
+                            Err(e) => {
+                                error!("Failed to encode PacketMessage: {}", e);
+                                Self::send_log_to_frontend(
+                                    app_handle.clone(),
+                                    &format!("编码RID数据包失败: {}", e),
+                                ).await;
+                                return;
+                            }
+                        };
+
+                        match simulator.build_and_send_rid(&ssid, encoded_data).await {
+                            Ok(_) => {
+                                info!("Queued RID for SSID: {}", ssid);
+                                let description = message.describe();
+                                debug!("Sent packet:\n{}", description);
+                                Self::send_log_to_frontend(
+                                    app_handle.clone(),
+                                    &format!("RID数据包已加入发送队列: SSID={}", ssid),
+                                ).await;
+                                Self::send_packet_to_frontend(app_handle.clone(), &description).await;
+                            }
+                            Err(e) => {
+                                error!("Failed to send RID: {}", e);
+                                Self::send_log_to_frontend(
+                                    app_handle.clone(),
+                                    &format!("发送RID失败: {}", e),
+                                ).await;
+                            }
                         }
                     }
                 } else {
@@ -263,6 +339,13 @@ impl MqttManager {
             let _ = handle.emit("log-message", message.to_string());
         }
     }
+
+    // 把已发送整包的字段描述树发给前端展示
+    async fn send_packet_to_frontend(app_handle: Arc<Mutex<Option<AppHandle>>>, description: &FieldNode) {
+        if let Some(handle) = app_handle.lock().await.as_ref() {
+            let _ = handle.emit("rid-packet", description);
+        }
+    }
 }
 
 // Global singleton
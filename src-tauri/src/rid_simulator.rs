@@ -1,14 +1,21 @@
-use pnet::datalink::{interfaces, Channel, NetworkInterface};
+use pnet::datalink::NetworkInterface;
 use libwifi::{FrameProtocolVersion, FrameType, FrameSubType};
 use libwifi::frame::Beacon;
 use libwifi::frame::components::{ManagementHeader, FrameControl, MacAddress, SequenceControl, StationInfo, VendorSpecificInfo};
-use tracing::{info, error};
+use tracing::{debug, info};
 use std::sync::atomic::{AtomicU16, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use crate::message::timestamp::SimulatedClock;
+use crate::config::AppConfig;
+use crate::transport::{Fanout, FanoutMode, InterfaceSelector, PnetInjector, RidTransport, TransportStats};
+use crate::transport::interfaces::{auto_detect, find_interface};
+/// 构造含 RID 的 Beacon 帧并经发送通道发出
+///
+/// 各方法只需 `&self`，可经 `Arc` 在 MQTT 事件循环和界面命令之间共享
 #[derive(Debug)]
 pub struct RidSimulator {
-    wifi_devices: Vec<NetworkInterface>,
+    clock: Option<SimulatedClock>, // 帧时间戳的时钟，未设置时使用系统当前时间
+    fanout: Fanout, // 帧的发送通道及各自的发送队列，未设置时启动时按配置选择 WiFi 网卡
 }
 
 static SEQ_COUNTER: AtomicU16 = AtomicU16::new(0);
@@ -39,43 +46,119 @@ struct RadioTapHeader {
 impl RidSimulator {
     pub fn new() -> Self {
         RidSimulator {
-            wifi_devices: Vec::new(),
+            clock: None,
+            fanout: Fanout::new(),
         }
     }
 
-    fn get_wifi_devices(&mut self) {
-        let interfaces = interfaces();
+    /// 使用模拟时钟生成 RadioTap 和 Beacon 的时间戳，用于回放
+    pub fn with_clock(clock: SimulatedClock) -> Self {
+        RidSimulator {
+            clock: Some(clock),
+            fanout: Fanout::new(),
+        }
+    }
 
-        info!("Available WiFi network devices:");
-        for interface in interfaces {
-            // 根据操作系统调整过滤条件, 分别为台式机, raspberry pi， 笔记本的wifi名称
-            if interface.name.contains("wlx") || interface.name.contains("wlan1") || interface.name.contains("wlp4") {
-                info!("Name: {}, MAC: {:?}", interface.name, interface.mac);
-                self.wifi_devices.push(interface);
-            } else {
-                info!("no wifi device: Name: {}, MAC: {:?}", interface.name, interface.mac);
-            }
+    /// 设置帧的发送通道，如 pcap 文件或内存缓冲，替换已有的全部通道
+    ///
+    /// 通道在模拟器的生命周期内保持打开，帧经有界队列交给通道发送
+    pub fn set_transport(&self, transport: Box<dyn RidTransport>) {
+        self.fanout.replace(vec![transport]);
+    }
+
+    /// 增加一个发送通道，帧按分发方式发往各通道
+    pub fn add_transport(&self, transport: Box<dyn RidTransport>) {
+        self.fanout.push(transport);
+    }
+
+    /// 设置多个发送通道时帧的分发方式
+    pub fn set_fanout_mode(&self, mode: FanoutMode) {
+        self.fanout.set_mode(mode);
+    }
+
+    pub fn fanout_mode(&self) -> FanoutMode {
+        self.fanout.mode()
+    }
+
+    /// 各发送通道的说明、运行状况和队列计数
+    pub fn transport_stats(&self) -> Vec<TransportStats> {
+        self.fanout.stats()
+    }
+
+    /// 等待已入队的帧全部发送完
+    pub async fn flush(&self) {
+        self.fanout.flush().await;
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        match &self.clock {
+            Some(clock) => clock.now(),
+            None => Utc::now(),
         }
     }
 
-    pub fn start_simulator(&mut self) {
-        self.get_wifi_devices();
-        if self.wifi_devices.is_empty() {
-            panic!("No WiFi devices found");
+    /// 按名称或 MAC 地址选择注入用的一块或多块网卡，替换当前的发送通道
+    pub fn select_interfaces(&self, selectors: &[InterfaceSelector]) -> Result<Vec<String>, String> {
+        if selectors.is_empty() {
+            return Err("未指定网卡".into());
         }
-        info!("device counter: {}", self.wifi_devices.len());
+        // 全部网卡都找到后再替换，避免只替换一部分
+        let interfaces = selectors.iter().map(find_interface).collect::<Result<Vec<_>, _>>()?;
+        self.use_interfaces(interfaces);
+        Ok(self.fanout.stats().into_iter().map(|stats| stats.transport).collect())
     }
 
-    pub fn build_and_send_rid(&self, ssid: &str, data: Vec<u8>) -> Result<String, String> {
-        let radiotap_bytes = self.build_radiotap_header();
-        let beacon_frame = self.build_rid_beacon(ssid, data.as_slice());
-        let full_frame = [radiotap_bytes, beacon_frame].concat();
-        self.send_beacon(&full_frame)?;  // 添加错误传播
-        info!("beacon frame: {:?}", full_frame);
+    fn use_interfaces(&self, interfaces: Vec<NetworkInterface>) {
+        self.fanout.replace(
+            interfaces
+                .into_iter()
+                .map(|interface| Box::new(PnetInjector::new(interface)) as Box<dyn RidTransport>)
+                .collect(),
+        );
+    }
+
+    /// 按配置文件设置分发方式；未设置发送通道时，按环境变量 RID_INTERFACE、配置文件的顺序选择网卡，
+    /// 都未指定时使用自动检测到的全部网卡
+    pub fn start_simulator(&self) -> Result<(), String> {
+        self.start_with_config(&AppConfig::load()?)
+    }
+
+    pub fn start_with_config(&self, config: &AppConfig) -> Result<(), String> {
+        if let Some(mode) = config.fanout_mode()? {
+            self.set_fanout_mode(mode);
+        }
+        // 已设置的发送通道 (如 pcapng 文件或界面选择的网卡) 保持不变
+        if self.fanout.is_empty() {
+            let selectors = config.interface_selectors()?;
+            if selectors.is_empty() {
+                self.use_interfaces(auto_detect()?);
+            } else {
+                self.select_interfaces(&selectors)?;
+            }
+        }
+        info!("fanout: {} transport(s), mode {}", self.fanout.len(), self.fanout.mode());
+        Ok(())
+    }
+
+    /// 构造帧并放入发送队列，队列满且等待超时时返回错误，发送结果见 `transport_stats`
+    pub async fn build_and_send_rid(&self, ssid: &str, data: Vec<u8>) -> Result<String, String> {
+        let sequence_number = SEQ_COUNTER.fetch_add(1, Ordering::SeqCst); // 序列号按802.11规范逐帧加1
+        let time = self.now();
+        let full_frame = Self::build_frame(ssid, data.as_slice(), sequence_number, time)?;
+        debug!("beacon frame: {:?}", full_frame);
+        self.send_beacon(ssid, full_frame, time).await?;  // 添加错误传播
         Ok("OK".to_string())  // 修改返回Result
     }
 
-    fn build_radiotap_header(&self) -> Vec<u8> {
+    /// 构造完整的发送帧：RadioTap 头 + 含 RID 的 Beacon 帧，相同输入得到逐字节相同的输出
+    pub fn build_frame(ssid: &str, rid_data: &[u8], sequence_number: u16, time: DateTime<Utc>) -> Result<Vec<u8>, String> {
+        let timestamp = time.timestamp_micros() as u64;
+        let radiotap_bytes = Self::build_radiotap_header(timestamp);
+        let beacon_frame = Self::build_rid_beacon(ssid, rid_data, sequence_number, timestamp)?;
+        Ok([radiotap_bytes, beacon_frame].concat())
+    }
+
+    fn build_radiotap_header(timestamp: u64) -> Vec<u8> {
         let mut header = RadioTapHeader {
             it_version: 0,
             it_pad: 0,
@@ -83,7 +166,7 @@ impl RidSimulator {
             it_present1: 0xa000_402f, // 示例：启用 Channel 字段（位掩码第15位）
             it_present2: 0xa000_0820, // 示例：启用 Channel 字段（位掩码第15位）
             it_present3: 0x0000_0820,
-            timestamp:  Utc::now().timestamp_micros() as u64,
+            timestamp,
             flags: 0x10,
             datarate: 0x0c,
             channel_info_freq: 2437u16,
@@ -120,17 +203,15 @@ impl RidSimulator {
         bytes.extend_from_slice(&header.antenna1.to_le_bytes());
         bytes.extend_from_slice(&header.antenna_signal3.to_le_bytes());
         bytes.extend_from_slice(&header.antenna2.to_le_bytes());
-        println!("byte len: {}", bytes.len());
         bytes
     }
 
-    // 构造含RID的Beacon帧
-    pub fn build_rid_beacon(&self, ssid: &str, rid_data: &[u8]) -> Vec<u8> {
-        let seq = SEQ_COUNTER.fetch_add(0x10, Ordering::SeqCst); // 序列号按802.11规范递增
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_micros() as u64; // 动态时间戳[1](@ref)
+    // 构造含RID的Beacon帧，序列号和时间戳 (微秒) 由调用方给出
+    // RID数据超出厂商自定义元素的长度上限 (251字节) 时返回错误
+    pub fn build_rid_beacon(ssid: &str, rid_data: &[u8], sequence_number: u16, timestamp: u64) -> Result<Vec<u8>, String> {
+        // OUI(3字节) + OUI类型(1字节) + RID数据，元素长度字段为1字节
+        let length = u8::try_from(rid_data.len() + 4)
+            .map_err(|_| format!("RID 数据过长: {} 字节，厂商自定义元素最多携带 {} 字节", rid_data.len(), u8::MAX as usize - 4))?;
 
         let header = ManagementHeader {
             frame_control: FrameControl {
@@ -145,7 +226,7 @@ impl RidSimulator {
             address_3: MacAddress([0x00, 0xE0, 0x4B, 0xD3, 0xDE, 0xD6]), // BSSID
             sequence_control: SequenceControl {
                 fragment_number: 0,
-                sequence_number: seq,
+                sequence_number,
             },
         };
 
@@ -164,7 +245,7 @@ impl RidSimulator {
         // 直接嵌入原始RID数据（不添加OUI头部）
         station_info.vendor_specific.push(VendorSpecificInfo {
             element_id: 221,             // IEEE自定义元素ID
-            length: (rid_data.len()) as u8, // 
+            length,
             oui: [0xfa, 0x0b, 0xbc],                 
             oui_type: 13,                 
             data: rid_data.to_vec(),
@@ -178,30 +259,149 @@ impl RidSimulator {
             station_info,
         };
 
-        beacon.encode()
+        Ok(beacon.encode())
     }
 
-    pub fn send_beacon(&self, beacon_data: &[u8]) -> Result<(), String> {
-        match pnet::datalink::channel(&self.wifi_devices[0], Default::default()) {
-            Ok(Channel::Ethernet(mut tx, _rx)) => {
-                if let Some(_) = tx.send_to(beacon_data, None) {
-                    info!("send rid.");
-                    Ok(())
-                } else {
-                    error!("Failed to send packet");
-                    Err("发送失败".into())
-                }
-            },
-            Ok(_) => {
-                error!("Unsupported channel type");
-                Err("不支持的通道类型".into())
-            }
-            Err(e) => {
-                error!("Failed to create channel: {}", e);
-                Err(format!("通道创建失败: {}", e))
-            }
-        }
+    /// 按分发方式把帧放入发送队列，`ssid` 用于轮流分配时区分无人机，`time` 为帧的生成时间
+    pub async fn send_beacon(&self, ssid: &str, beacon_data: Vec<u8>, time: DateTime<Utc>) -> Result<(), String> {
+        self.fanout.send(ssid, beacon_data, time).await
     }
 
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::message::message::Message;
+    use crate::message::packet_message::PacketMessage;
+    use crate::message::protocol::{ProtocolVersion, WireFormat};
+    use crate::transport::MemorySink;
+    use chrono::TimeZone;
+
+    // 完整帧的参考数据，帧布局有意变更时按新的输出更新 tests/fixtures 下的文件
+    const DJI_COMPAT_PACKET: &str = include_str!("../tests/fixtures/dji_compat_packet.hex");
+    const DJI_COMPAT_BEACON: &str = include_str!("../tests/fixtures/dji_compat_beacon.hex");
+    const ASTM_F3411_PACKET: &str = include_str!("../tests/fixtures/astm_f3411_packet.hex");
+    const ASTM_F3411_BEACON: &str = include_str!("../tests/fixtures/astm_f3411_beacon.hex");
+
+    fn fixture(text: &str) -> Vec<u8> {
+        hex::decode(text.trim()).unwrap()
+    }
+
+    fn frame_time() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn dji_compat_frame_matches_fixture() {
+        let packet_bytes = fixture(DJI_COMPAT_PACKET);
+        let packet = PacketMessage::from_bytes(&packet_bytes).unwrap();
+        let frame = RidSimulator::build_frame(&packet.get_ssid(), &packet_bytes, 42, frame_time()).unwrap();
+        assert_eq!(hex::encode(frame), DJI_COMPAT_BEACON.trim());
+    }
+
+    #[test]
+    fn astm_f3411_frame_matches_fixture() {
+        let packet_bytes = fixture(ASTM_F3411_PACKET);
+        let format = WireFormat::astm(ProtocolVersion::F3411_22a);
+        let packet = PacketMessage::from_bytes_with(&packet_bytes, &format).unwrap();
+        let frame = RidSimulator::build_frame(&packet.get_ssid(), &packet_bytes, 43, frame_time()).unwrap();
+        assert_eq!(hex::encode(frame), ASTM_F3411_BEACON.trim());
+    }
+
+    #[test]
+    fn vendor_element_length_covers_oui_and_payload() {
+        let payload = fixture(DJI_COMPAT_PACKET);
+        let beacon = RidSimulator::build_rid_beacon("RID-TEST", &payload, 0, 0).unwrap();
+        let oui_start = beacon.windows(4).position(|w| w == [0xfa, 0x0b, 0xbc, 13]).unwrap();
+        assert_eq!(beacon[oui_start - 2], 221);
+        assert_eq!(beacon[oui_start - 1] as usize, payload.len() + 4);
+        assert_eq!(&beacon[oui_start + 4..], payload.as_slice());
+    }
+
+    #[test]
+    fn rejects_payload_exceeding_vendor_element() {
+        assert!(RidSimulator::build_rid_beacon("RID-TEST", &[0u8; 251], 0, 0).is_ok());
+        assert!(RidSimulator::build_rid_beacon("RID-TEST", &[0u8; 252], 0, 0).is_err());
+        assert!(RidSimulator::build_frame("RID-TEST", &[0u8; 252], 0, frame_time()).is_err());
+    }
+
+    #[tokio::test]
+    async fn sends_frames_through_transport() {
+        let sink = MemorySink::new();
+        let simulator = RidSimulator::with_clock(SimulatedClock::new(frame_time()));
+        simulator.set_transport(Box::new(sink.clone()));
+        simulator.start_with_config(&AppConfig::default()).unwrap();
+
+        let payload = fixture(DJI_COMPAT_PACKET);
+        simulator.build_and_send_rid("RID-TEST", payload.clone()).await.unwrap();
+        simulator.flush().await;
+
+        let frames = sink.frames();
+        assert_eq!(frames.len(), 1);
+        assert!(frames[0].ends_with(&payload));
+        let stats = simulator.transport_stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].transport, "memory");
+        assert_eq!(stats[0].health.frames_sent, 1);
+        assert_eq!(stats[0].queue.sent, 1);
+    }
+
+    #[tokio::test]
+    async fn send_without_transport_fails() {
+        let simulator = RidSimulator::new();
+        assert!(simulator.build_and_send_rid("RID-TEST", vec![0u8; 4]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn shared_simulator_sends_from_spawned_task() {
+        let sinks = [MemorySink::new(), MemorySink::new()];
+        let simulator = Arc::new(RidSimulator::with_clock(SimulatedClock::new(frame_time())));
+        simulator.set_transport(Box::new(sinks[0].clone()));
+
+        // 不加外层锁，经 Arc 在其他任务中入队
+        let payload = fixture(DJI_COMPAT_PACKET);
+        let sender = {
+            let simulator = simulator.clone();
+            let payload = payload.clone();
+            tokio::spawn(async move { simulator.build_and_send_rid("RID-TEST", payload).await })
+        };
+        sender.await.unwrap().unwrap();
+        simulator.flush().await;
+
+        simulator.set_transport(Box::new(sinks[1].clone()));
+        simulator.set_fanout_mode(FanoutMode::Broadcast);
+        simulator.build_and_send_rid("RID-TEST", payload).await.unwrap();
+        simulator.flush().await;
+        assert_eq!(sinks[0].frames().len(), 1);
+        assert_eq!(sinks[1].frames().len(), 1);
+    }
+
+    #[test]
+    fn config_fanout_mode_applies_to_existing_transports() {
+        let sinks = [MemorySink::new(), MemorySink::new()];
+        let simulator = RidSimulator::new();
+        simulator.set_transport(Box::new(sinks[0].clone()));
+        simulator.add_transport(Box::new(sinks[1].clone()));
+
+        let config = AppConfig { fanout: Some(FanoutMode::Broadcast), ..Default::default() };
+        simulator.start_with_config(&config).unwrap();
+        assert_eq!(simulator.fanout_mode(), FanoutMode::Broadcast);
+        assert_eq!(simulator.transport_stats().len(), 2);
+    }
+
+    #[test]
+    fn simulated_clock_drives_frame_time() {
+        let clock = SimulatedClock::new(frame_time());
+        let simulator = RidSimulator::with_clock(clock.clone());
+        assert_eq!(simulator.now(), frame_time());
+
+        let payload = fixture(DJI_COMPAT_PACKET);
+        let first = RidSimulator::build_frame("RID-TEST", &payload, 7, simulator.now()).unwrap();
+        assert_eq!(first, RidSimulator::build_frame("RID-TEST", &payload, 7, simulator.now()).unwrap());
+
+        clock.advance(chrono::Duration::milliseconds(100));
+        assert_ne!(first, RidSimulator::build_frame("RID-TEST", &payload, 7, simulator.now()).unwrap());
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{QueueStats, RidTransport, SendQueue, TransportHealth};
+
+/// 多个发送通道时帧的分发方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FanoutMode {
+    /// 只使用第一个通道
+    #[default]
+    Single,
+    /// 每帧发往全部通道，各网卡可设在不同信道上同时广播
+    Broadcast,
+    /// 无人机首次出现时分配到无人机最少的通道，之后固定使用同一通道，长时间未出现的无人机释放分配
+    RoundRobin,
+}
+
+impl FromStr for FanoutMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "single" => Ok(FanoutMode::Single),
+            "broadcast" => Ok(FanoutMode::Broadcast),
+            "round_robin" => Ok(FanoutMode::RoundRobin),
+            _ => Err(format!("未知的分发方式: {} (可选 single, broadcast, round_robin)", s)),
+        }
+    }
+}
+
+impl fmt::Display for FanoutMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FanoutMode::Single => "single",
+            FanoutMode::Broadcast => "broadcast",
+            FanoutMode::RoundRobin => "round_robin",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 单个发送通道的统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransportStats {
+    pub transport: String,       // 通道说明，如网卡名称
+    pub health: TransportHealth, // 通道的运行状况
+    pub queue: QueueStats,       // 通道发送队列的计数
+    pub drones: usize,           // 轮流分配时分到此通道的无人机数
+}
+
+/// 轮流分配时一架无人机的通道分配
+#[derive(Debug, Clone, Copy)]
+struct Assignment {
+    index: usize,             // 通道序号
+    last_seen: DateTime<Utc>, // 最近一帧的生成时间
+}
+
+/// 把帧分发到一个或多个发送通道，每个通道有各自的发送队列和工作线程
+///
+/// 各方法只需 `&self`：通道和分发方式由内部的锁保护，入队前取出选中通道的句柄并释放锁，
+/// 入队等待期间不持有任何锁，切换网卡或分发方式不会被正在入队的帧拖住
+pub struct Fanout {
+    queues: Mutex<Vec<Arc<SendQueue>>>,
+    mode: Mutex<FanoutMode>,
+    assignments: Mutex<HashMap<String, Assignment>>, // 无人机 (SSID) 到通道的分配，轮流分配时使用
+}
+
+impl Default for Fanout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fanout {
+    /// 轮流分配时最多记住的无人机数，达到上限时释放最久未出现的一架
+    pub const MAX_ASSIGNMENTS: usize = 1024;
+    /// 按帧的生成时间超过此时长未出现的无人机释放通道分配
+    pub const ASSIGNMENT_TTL: TimeDelta = TimeDelta::seconds(60);
+
+    pub fn new() -> Self {
+        Self {
+            queues: Mutex::new(Vec::new()),
+            mode: Mutex::new(FanoutMode::Single),
+            assignments: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.lock().unwrap().is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.queues.lock().unwrap().len()
+    }
+
+    pub fn mode(&self) -> FanoutMode {
+        *self.mode.lock().unwrap()
+    }
+
+    /// 切换分发方式，已有的无人机分配随之清空
+    pub fn set_mode(&self, mode: FanoutMode) {
+        info!("fanout mode: {}", mode);
+        *self.mode.lock().unwrap() = mode;
+        self.assignments.lock().unwrap().clear();
+    }
+
+    /// 增加一个发送通道，经有界队列发送
+    pub fn push(&self, transport: Box<dyn RidTransport>) {
+        let queue = Self::queue(transport);
+        self.queues.lock().unwrap().push(queue);
+    }
+
+    /// 移除全部发送通道，已入队的帧由各自的工作线程发送完
+    pub fn clear(&self) {
+        self.replace(Vec::new());
+    }
+
+    /// 一次替换全部发送通道，入队中的帧不会分到只替换了一部分的通道
+    pub fn replace(&self, transports: Vec<Box<dyn RidTransport>>) {
+        let queues = transports.into_iter().map(Self::queue).collect();
+        let mut current = self.queues.lock().unwrap();
+        *current = queues;
+        self.assignments.lock().unwrap().clear();
+    }
+
+    fn queue(transport: Box<dyn RidTransport>) -> Arc<SendQueue> {
+        info!("transport: {}", transport.describe());
+        Arc::new(SendQueue::new(transport, SendQueue::DEFAULT_CAPACITY, SendQueue::DEFAULT_MAX_WAIT))
+    }
+
+    /// 按分发方式选出发送此无人机帧的通道，`time` 为帧的生成时间
+    fn targets(&self, drone: &str, time: DateTime<Utc>) -> Vec<Arc<SendQueue>> {
+        let queues = self.queues.lock().unwrap();
+        if queues.is_empty() {
+            return Vec::new();
+        }
+        match self.mode() {
+            FanoutMode::Single => vec![queues[0].clone()],
+            FanoutMode::Broadcast => queues.clone(),
+            FanoutMode::RoundRobin => {
+                let mut assignments = self.assignments.lock().unwrap();
+                let index = match assignments.get_mut(drone) {
+                    Some(assignment) => {
+                        assignment.last_seen = time;
+                        assignment.index
+                    }
+                    None => {
+                        Self::evict(&mut assignments, time);
+                        let index = Self::least_assigned(&assignments, queues.len());
+                        assignments.insert(drone.to_string(), Assignment { index, last_seen: time });
+                        index
+                    }
+                };
+                vec![queues[index].clone()]
+            }
+        }
+    }
+
+    /// 释放超过 `ASSIGNMENT_TTL` 未出现的无人机，仍达到上限时释放最久未出现的一架
+    fn evict(assignments: &mut HashMap<String, Assignment>, time: DateTime<Utc>) {
+        assignments.retain(|_, assignment| time - assignment.last_seen <= Self::ASSIGNMENT_TTL);
+        if assignments.len() >= Self::MAX_ASSIGNMENTS {
+            let oldest = assignments
+                .iter()
+                .min_by_key(|(_, assignment)| assignment.last_seen)
+                .map(|(drone, _)| drone.clone());
+            if let Some(drone) = oldest {
+                assignments.remove(&drone);
+            }
+        }
+    }
+
+    /// 分到无人机最少的通道，数量相同时取序号小的
+    fn least_assigned(assignments: &HashMap<String, Assignment>, count: usize) -> usize {
+        let mut drones = vec![0usize; count];
+        for assignment in assignments.values() {
+            if let Some(drones) = drones.get_mut(assignment.index) {
+                *drones += 1;
+            }
+        }
+        (0..count).min_by_key(|&index| drones[index]).unwrap_or(0)
+    }
+
+    /// 把一帧放入选中通道的队列，任一通道入队失败时返回错误，`time` 为帧的生成时间
+    pub async fn send(&self, drone: &str, frame: Vec<u8>, time: DateTime<Utc>) -> Result<(), String> {
+        let targets = self.targets(drone, time);
+        let Some((last, rest)) = targets.split_last() else {
+            return Err("未配置发送通道".into());
+        };
+        let mut errors = Vec::new();
+        for queue in rest {
+            if let Err(e) = queue.send(frame.clone(), time).await {
+                errors.push(format!("{}: {}", queue.describe(), e));
+            }
+        }
+        if let Err(e) = last.send(frame, time).await {
+            errors.push(format!("{}: {}", last.describe(), e));
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+
+    /// 等待全部通道中已入队的帧发送完
+    pub async fn flush(&self) {
+        let queues = self.queues.lock().unwrap().clone();
+        for queue in queues {
+            queue.flush().await;
+        }
+    }
+
+    /// 各通道的统计，顺序与添加顺序相同
+    pub fn stats(&self) -> Vec<TransportStats> {
+        let queues = self.queues.lock().unwrap();
+        let assignments = self.assignments.lock().unwrap();
+        queues
+            .iter()
+            .enumerate()
+            .map(|(index, queue)| TransportStats {
+                transport: queue.describe().to_string(),
+                health: queue.health(),
+                queue: queue.stats(),
+                drones: assignments.values().filter(|assignment| assignment.index == index).count(),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for Fanout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Fanout")
+            .field("mode", &self.mode())
+            .field("queues", &*self.queues.lock().unwrap())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemorySink;
+    use std::collections::BTreeSet;
+
+    fn fanout(mode: FanoutMode, count: usize) -> (Fanout, Vec<MemorySink>) {
+        let fanout = Fanout::new();
+        fanout.set_mode(mode);
+        let sinks: Vec<MemorySink> = (0..count).map(|_| MemorySink::new()).collect();
+        for sink in &sinks {
+            fanout.push(Box::new(sink.clone()));
+        }
+        (fanout, sinks)
+    }
+
+    #[test]
+    fn parses_mode_names() {
+        assert_eq!("round-robin".parse::<FanoutMode>(), Ok(FanoutMode::RoundRobin));
+        assert_eq!("Broadcast".parse::<FanoutMode>(), Ok(FanoutMode::Broadcast));
+        assert!("all".parse::<FanoutMode>().is_err());
+        assert_eq!(FanoutMode::RoundRobin.to_string().parse::<FanoutMode>(), Ok(FanoutMode::RoundRobin));
+    }
+
+    #[tokio::test]
+    async fn broadcast_sends_every_frame_on_all_transports() {
+        let (fanout, sinks) = fanout(FanoutMode::Broadcast, 3);
+        fanout.send("RID-A", vec![1], Utc::now()).await.unwrap();
+        fanout.send("RID-B", vec![2], Utc::now()).await.unwrap();
+        fanout.flush().await;
+
+        for sink in &sinks {
+            assert_eq!(sink.frames(), vec![vec![1], vec![2]]);
+        }
+        assert!(fanout.stats().iter().all(|stats| stats.queue.sent == 2));
+    }
+
+    #[tokio::test]
+    async fn round_robin_keeps_each_drone_on_one_transport() {
+        let (fanout, sinks) = fanout(FanoutMode::RoundRobin, 2);
+        for frame in 0..3u8 {
+            for drone in ["RID-A", "RID-B", "RID-C"] {
+                fanout.send(drone, vec![drone.as_bytes()[4], frame], Utc::now()).await.unwrap();
+            }
+        }
+        fanout.flush().await;
+
+        let drones_on = |sink: &MemorySink| -> BTreeSet<u8> { sink.frames().iter().map(|frame| frame[0]).collect() };
+        assert_eq!(drones_on(&sinks[0]), BTreeSet::from([b'A', b'C']));
+        assert_eq!(drones_on(&sinks[1]), BTreeSet::from([b'B']));
+        let stats = fanout.stats();
+        assert_eq!((stats[0].drones, stats[1].drones), (2, 1));
+        assert_eq!((stats[0].queue.sent, stats[1].queue.sent), (6, 3));
+    }
+
+    #[tokio::test]
+    async fn round_robin_releases_drones_not_seen_recently() {
+        let (fanout, sinks) = fanout(FanoutMode::RoundRobin, 2);
+        let start = Utc::now();
+        fanout.send("RID-A", vec![b'A'], start).await.unwrap();
+        fanout.send("RID-B", vec![b'B'], start).await.unwrap();
+        fanout.send("RID-A", vec![b'A'], start + TimeDelta::seconds(30)).await.unwrap();
+
+        // RID-B 超时释放，新出现的 RID-C 分到空出的通道
+        let later = start + Fanout::ASSIGNMENT_TTL + TimeDelta::seconds(1);
+        fanout.send("RID-C", vec![b'C'], later).await.unwrap();
+        fanout.flush().await;
+        assert_eq!(sinks[1].frames(), vec![vec![b'B'], vec![b'C']]);
+        let stats = fanout.stats();
+        assert_eq!((stats[0].drones, stats[1].drones), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn round_robin_assignments_are_bounded() {
+        let (fanout, _sinks) = fanout(FanoutMode::RoundRobin, 2);
+        let start = Utc::now();
+        for drone in 0..=Fanout::MAX_ASSIGNMENTS {
+            let time = start + TimeDelta::milliseconds(drone as i64);
+            fanout.send(&format!("RID-{}", drone), vec![0], time).await.unwrap();
+        }
+        fanout.flush().await;
+
+        let assignments = fanout.assignments.lock().unwrap();
+        assert_eq!(assignments.len(), Fanout::MAX_ASSIGNMENTS);
+        assert!(!assignments.contains_key("RID-0"));
+        assert!(assignments.contains_key(&format!("RID-{}", Fanout::MAX_ASSIGNMENTS)));
+    }
+
+    #[tokio::test]
+    async fn single_uses_first_transport_only() {
+        let (fanout, sinks) = fanout(FanoutMode::Single, 2);
+        fanout.send("RID-A", vec![1], Utc::now()).await.unwrap();
+        fanout.flush().await;
+        assert_eq!(sinks[0].frames().len(), 1);
+        assert!(sinks[1].frames().is_empty());
+        assert!(Fanout::new().send("RID-A", vec![1], Utc::now()).await.is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use pnet::datalink::{interfaces, MacAddr, NetworkInterface};
+use serde::Serialize;
+use tracing::{info, warn};
+
+/// 旧版本按名称匹配的网卡，分别为台式机 USB 网卡、raspberry pi 外接网卡和笔记本网卡
+const LEGACY_NAME_PATTERNS: [&str; 3] = ["wlx", "wlan1", "wlp4"];
+
+/// Linux 下监听模式网卡的链路类型 (ARPHRD_IEEE80211_RADIOTAP)
+const ARPHRD_IEEE80211_RADIOTAP: &str = "803";
+
+/// 按名称或 MAC 地址指定注入用的网卡
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterfaceSelector {
+    Name(String),
+    Mac(MacAddr),
+}
+
+impl InterfaceSelector {
+    pub fn matches(&self, interface: &NetworkInterface) -> bool {
+        match self {
+            InterfaceSelector::Name(name) => interface.name == *name,
+            InterfaceSelector::Mac(mac) => interface.mac == Some(*mac),
+        }
+    }
+}
+
+impl FromStr for InterfaceSelector {
+    type Err = String;
+
+    /// 能解析为 MAC 地址 (如 00:e0:4b:d3:de:d6) 时按 MAC 匹配，否则按名称匹配
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("网卡名称或 MAC 地址为空".into());
+        }
+        match MacAddr::from_str(s) {
+            Ok(mac) => Ok(InterfaceSelector::Mac(mac)),
+            Err(_) => Ok(InterfaceSelector::Name(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for InterfaceSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterfaceSelector::Name(name) => write!(f, "{}", name),
+            InterfaceSelector::Mac(mac) => write!(f, "{}", mac),
+        }
+    }
+}
+
+/// 候选网卡及其状态，供前端选择
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InterfaceInfo {
+    pub name: String,
+    pub mac: Option<String>,
+    pub ips: Vec<String>,
+    pub up: bool,       // 已启用
+    pub running: bool,  // 链路已就绪
+    pub wireless: bool, // 无线网卡
+    pub monitor: bool,  // 处于监听模式，可以注入帧
+}
+
+impl InterfaceInfo {
+    pub fn from_interface(interface: &NetworkInterface) -> Self {
+        let sysfs = Path::new("/sys/class/net").join(&interface.name);
+        let monitor = std::fs::read_to_string(sysfs.join("type"))
+            .map(|link_type| link_type.trim() == ARPHRD_IEEE80211_RADIOTAP)
+            .unwrap_or(false);
+        Self {
+            name: interface.name.clone(),
+            mac: interface.mac.map(|mac| mac.to_string()),
+            ips: interface.ips.iter().map(|ip| ip.to_string()).collect(),
+            up: interface.is_up(),
+            running: interface.is_running(),
+            wireless: monitor || sysfs.join("wireless").exists() || sysfs.join("phy80211").exists(),
+            monitor,
+        }
+    }
+}
+
+/// 列出除回环网卡以外的全部网卡
+pub fn list_interfaces() -> Vec<InterfaceInfo> {
+    interfaces()
+        .iter()
+        .filter(|interface| !interface.is_loopback())
+        .map(InterfaceInfo::from_interface)
+        .collect()
+}
+
+/// 按名称或 MAC 地址查找网卡
+pub fn find_interface(selector: &InterfaceSelector) -> Result<NetworkInterface, String> {
+    let interface = interfaces()
+        .into_iter()
+        .find(|interface| selector.matches(interface))
+        .ok_or_else(|| format!("未找到网卡: {}", selector))?;
+    let info = InterfaceInfo::from_interface(&interface);
+    if !info.monitor {
+        warn!("interface {} is not in monitor mode, injection may fail", interface.name);
+    }
+    Ok(interface)
+}
+
+/// 未指定网卡时自动选择，按优先级返回全部候选：先是监听模式网卡 (已启用的在前)，
+/// 其次是旧版本按名称匹配的网卡
+pub fn auto_detect() -> Result<Vec<NetworkInterface>, String> {
+    let candidates: Vec<NetworkInterface> = interfaces()
+        .into_iter()
+        .filter(|interface| !interface.is_loopback())
+        .collect();
+    let infos: Vec<InterfaceInfo> = candidates.iter().map(InterfaceInfo::from_interface).collect();
+    for info in &infos {
+        info!("interface: {} mac: {:?} up: {} wireless: {} monitor: {}", info.name, info.mac, info.up, info.wireless, info.monitor);
+    }
+    let ranked = rank_candidates(&infos);
+    if ranked.is_empty() {
+        return Err(format!(
+            "未找到可用的 WiFi 网卡，请通过 RID_INTERFACE 环境变量、配置文件或界面指定网卡 (现有网卡: {})",
+            infos.iter().map(|info| info.name.as_str()).collect::<Vec<_>>().join(", ")
+        ));
+    }
+    Ok(ranked.into_iter().map(|index| candidates[index].clone()).collect())
+}
+
+fn rank_candidates(infos: &[InterfaceInfo]) -> Vec<usize> {
+    let legacy = |info: &InterfaceInfo| LEGACY_NAME_PATTERNS.iter().any(|pattern| info.name.contains(pattern));
+    let mut ranked: Vec<usize> = (0..infos.len())
+        .filter(|&index| infos[index].monitor || legacy(&infos[index]))
+        .collect();
+    ranked.sort_by_key(|&index| (!infos[index].monitor, !infos[index].up));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(name: &str, up: bool, monitor: bool) -> InterfaceInfo {
+        InterfaceInfo {
+            name: name.to_string(),
+            mac: None,
+            ips: Vec::new(),
+            up,
+            running: up,
+            wireless: monitor || name.starts_with("wl"),
+            monitor,
+        }
+    }
+
+    #[test]
+    fn parses_name_or_mac() {
+        assert_eq!(
+            "00:e0:4b:d3:de:d6".parse::<InterfaceSelector>(),
+            Ok(InterfaceSelector::Mac(MacAddr::new(0x00, 0xe0, 0x4b, 0xd3, 0xde, 0xd6)))
+        );
+        assert_eq!(" wlan1 ".parse::<InterfaceSelector>(), Ok(InterfaceSelector::Name("wlan1".into())));
+        assert!("  ".parse::<InterfaceSelector>().is_err());
+    }
+
+    #[test]
+    fn ranks_monitor_mode_interfaces_first() {
+        let infos = [info("eth0", true, false), info("wlan1", true, false), info("mon0", false, true), info("mon1", true, true)];
+        assert_eq!(rank_candidates(&infos), vec![3, 2, 1]);
+        assert_eq!(rank_candidates(&infos[..2]), vec![1]);
+        assert!(rank_candidates(&[info("eth0", true, false), info("wlan0", true, false)]).is_empty());
+    }
+}
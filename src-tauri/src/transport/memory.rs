@@ -0,0 +1,53 @@
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Utc};
+
+use super::{RidTransport, TransportHealth};
+
+/// 把帧保存在内存中的发送通道，用于测试
+///
+/// 克隆得到的实例共享同一个缓冲区，交给模拟器后仍可通过克隆读取已发送的帧
+#[derive(Debug, Clone)]
+pub struct MemorySink {
+    frames: Arc<Mutex<Vec<Vec<u8>>>>,
+    health: Arc<Mutex<TransportHealth>>,
+}
+
+impl Default for MemorySink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        Self {
+            frames: Arc::new(Mutex::new(Vec::new())),
+            health: Arc::new(Mutex::new(TransportHealth::new())),
+        }
+    }
+
+    /// 已发送的帧，按发送顺序排列
+    pub fn frames(&self) -> Vec<Vec<u8>> {
+        self.frames.lock().unwrap().clone()
+    }
+
+    pub fn clear(&self) {
+        self.frames.lock().unwrap().clear();
+    }
+}
+
+impl RidTransport for MemorySink {
+    fn send_frame(&mut self, frame: &[u8], _time: DateTime<Utc>) -> Result<(), String> {
+        self.frames.lock().unwrap().push(frame.to_vec());
+        self.health.lock().unwrap().record(&Ok(()));
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        "memory".to_string()
+    }
+
+    fn health(&self) -> TransportHealth {
+        self.health.lock().unwrap().clone()
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+pub mod pnet_injector;
+pub mod pcapng;
+pub mod memory;
+pub mod queue;
+pub mod interfaces;
+pub mod fanout;
+
+pub use fanout::{Fanout, FanoutMode, TransportStats};
+pub use interfaces::{InterfaceInfo, InterfaceSelector};
+pub use memory::MemorySink;
+pub use pcapng::{PcapngRotation, PcapngWriter};
+pub use pnet_injector::PnetInjector;
+pub use queue::{QueueStats, SendQueue};
+
+/// 发送通道的运行状况
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TransportHealth {
+    pub healthy: bool,              // 最近一次发送是否成功，尚未发送时为 true
+    pub frames_sent: u64,           // 累计发送成功的帧数
+    pub send_errors: u64,           // 累计发送失败的次数
+    pub last_error: Option<String>, // 最近一次发送失败的原因
+}
+
+impl TransportHealth {
+    pub fn new() -> Self {
+        Self {
+            healthy: true,
+            ..Self::default()
+        }
+    }
+
+    /// 按发送结果更新计数
+    pub fn record(&mut self, result: &Result<(), String>) {
+        match result {
+            Ok(()) => {
+                self.healthy = true;
+                self.frames_sent += 1;
+            }
+            Err(e) => {
+                self.healthy = false;
+                self.send_errors += 1;
+                self.last_error = Some(e.clone());
+            }
+        }
+    }
+}
+
+/// RID 帧的发送通道，帧为 RadioTap 头 + Beacon 帧
+///
+/// 网卡注入、pcap 文件、UDP 转发、内存缓冲以及后续的蓝牙后端都实现此 trait，
+/// MQTT 和报文代码不关心帧最终发往何处
+pub trait RidTransport: Send + fmt::Debug {
+    /// 发送一帧，失败时返回可直接展示给前端的错误信息
+    ///
+    /// `time` 为帧的生成时间，与帧内 RadioTap 和 Beacon 的时间戳一致，写文件的通道以此作为抓包时间
+    fn send_frame(&mut self, frame: &[u8], time: DateTime<Utc>) -> Result<(), String>;
+
+    /// 通道的简短说明，如网卡名称
+    fn describe(&self) -> String;
+
+    /// 通道的运行状况
+    fn health(&self) -> TransportHealth;
+}
@@ -0,0 +1,323 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Duration, Utc};
+use tracing::info;
+
+use super::{RidTransport, TransportHealth};
+
+// pcapng 块类型
+const SECTION_HEADER_BLOCK: u32 = 0x0A0D_0D0A;
+const INTERFACE_DESCRIPTION_BLOCK: u32 = 0x0000_0001;
+const ENHANCED_PACKET_BLOCK: u32 = 0x0000_0006;
+// 字节序标识，读取端据此判断小端序
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+// LINKTYPE_IEEE802_11_RADIOTAP
+const LINKTYPE_RADIOTAP: u16 = 127;
+// 选项代码
+const OPT_END_OF_OPT: u16 = 0;
+const OPT_SHB_USERAPPL: u16 = 4;
+const OPT_IF_NAME: u16 = 2;
+
+/// pcapng 文件的轮转条件，均未设置时只写一个文件
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PcapngRotation {
+    pub max_bytes: Option<u64>,        // 单个文件的最大字节数
+    pub max_duration: Option<Duration>, // 单个文件覆盖的最长时间
+}
+
+/// 把每一帧写入 pcapng 文件的发送通道，链路类型为 RadioTap (127)，无需 WiFi 硬件
+///
+/// 时间戳精度为微秒，取自帧的生成时间，与帧内 RadioTap 和 Beacon 的时间戳一致。
+/// 发生轮转时第一个文件使用给定路径，之后的文件在文件名后追加序号，如 `rid.pcapng`、
+/// `rid-1.pcapng`、`rid-2.pcapng`。已存在的文件不会被覆盖，跳到下一个未使用的序号
+#[derive(Debug)]
+pub struct PcapngWriter {
+    path: PathBuf,
+    rotation: PcapngRotation,
+    writer: BufWriter<File>,
+    file_index: u32,
+    file_bytes: u64,
+    file_started: Option<DateTime<Utc>>, // 当前文件第一帧的时间
+    health: TransportHealth,
+}
+
+impl PcapngWriter {
+    /// 创建文件并写入节头块和接口描述块，给定路径已存在时使用下一个未使用的序号
+    pub fn create(path: impl Into<PathBuf>, rotation: PcapngRotation) -> Result<Self, String> {
+        let path = path.into();
+        let (file_index, writer, file_bytes) = Self::open_next(&path, 0)?;
+        info!("pcapng output: {}", Self::indexed_path(&path, file_index).display());
+        Ok(Self {
+            path,
+            rotation,
+            writer,
+            file_index,
+            file_bytes,
+            file_started: None,
+            health: TransportHealth::new(),
+        })
+    }
+
+    /// 按环境变量创建，未设置 `RID_PCAPNG_PATH` 时返回 None
+    ///
+    /// - `RID_PCAPNG_PATH`: 输出文件路径
+    /// - `RID_PCAPNG_MAX_BYTES`: 单个文件的最大字节数
+    /// - `RID_PCAPNG_MAX_SECONDS`: 单个文件覆盖的最长秒数
+    pub fn from_env() -> Option<Result<Self, String>> {
+        let path = std::env::var_os("RID_PCAPNG_PATH")?;
+        Some(Self::rotation_from_env().and_then(|rotation| Self::create(path, rotation)))
+    }
+
+    fn rotation_from_env() -> Result<PcapngRotation, String> {
+        Ok(PcapngRotation {
+            max_bytes: Self::env_number("RID_PCAPNG_MAX_BYTES")?,
+            max_duration: Self::env_number("RID_PCAPNG_MAX_SECONDS")?.map(|seconds| Duration::seconds(seconds as i64)),
+        })
+    }
+
+    fn env_number(name: &str) -> Result<Option<u64>, String> {
+        match std::env::var(name) {
+            Ok(value) => value.trim().parse().map(Some).map_err(|_| format!("{} 不是有效的数字: {}", name, value)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// 当前正在写入的文件
+    pub fn current_path(&self) -> PathBuf {
+        Self::indexed_path(&self.path, self.file_index)
+    }
+
+    fn indexed_path(path: &Path, index: u32) -> PathBuf {
+        if index == 0 {
+            return path.to_path_buf();
+        }
+        let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let name = match path.extension() {
+            Some(ext) => format!("{}-{}.{}", stem, index, ext.to_string_lossy()),
+            None => format!("{}-{}", stem, index),
+        };
+        path.with_file_name(name)
+    }
+
+    // 从 `index` 起找到第一个不存在的文件并创建，不覆盖已有的抓包
+    fn open_next(path: &Path, mut index: u32) -> Result<(u32, BufWriter<File>, u64), String> {
+        loop {
+            let indexed = Self::indexed_path(path, index);
+            match Self::open(&indexed) {
+                Ok((writer, file_bytes)) => return Ok((index, writer, file_bytes)),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    index = index.checked_add(1).ok_or_else(|| format!("没有可用的 pcapng 文件序号: {}", path.display()))?;
+                }
+                Err(e) => return Err(format!("创建 pcapng 文件失败: {}: {}", indexed.display(), e)),
+            }
+        }
+    }
+
+    fn open(path: &Path) -> std::io::Result<(BufWriter<File>, u64)> {
+        let file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        let mut writer = BufWriter::new(file);
+        let header = [Self::section_header_block(), Self::interface_description_block()].concat();
+        writer.write_all(&header)?;
+        writer.flush()?;
+        Ok((writer, header.len() as u64))
+    }
+
+    fn should_rotate(&self, block_length: u64, time: DateTime<Utc>) -> bool {
+        // 文件中至少保留一帧，避免单帧超过上限时反复轮转
+        let has_packets = self.file_bytes > (Self::section_header_block().len() + Self::interface_description_block().len()) as u64;
+        let too_large = self.rotation.max_bytes.is_some_and(|max| self.file_bytes + block_length > max);
+        let too_old = self.rotation.max_duration
+            .is_some_and(|max| self.file_started.is_some_and(|started| time - started >= max));
+        has_packets && (too_large || too_old)
+    }
+
+    fn rotate(&mut self) -> Result<(), String> {
+        let (file_index, writer, file_bytes) = Self::open_next(&self.path, self.file_index + 1)?;
+        self.file_index = file_index;
+        self.writer = writer;
+        self.file_bytes = file_bytes;
+        self.file_started = None;
+        info!("pcapng rotated: {}", self.current_path().display());
+        Ok(())
+    }
+
+    fn write_frame(&mut self, frame: &[u8], time: DateTime<Utc>) -> Result<(), String> {
+        let block = Self::enhanced_packet_block(frame, time.timestamp_micros() as u64);
+        if self.should_rotate(block.len() as u64, time) {
+            self.rotate()?;
+        }
+        // 每帧落盘，抓包过程中即可用 Wireshark 打开
+        self.writer.write_all(&block).map_err(|e| format!("写入 pcapng 文件失败: {}", e))?;
+        self.writer.flush().map_err(|e| format!("写入 pcapng 文件失败: {}", e))?;
+        self.file_bytes += block.len() as u64;
+        self.file_started.get_or_insert(time);
+        Ok(())
+    }
+
+    // 块结构：类型 + 总长度 + 内容 (按4字节对齐) + 总长度
+    fn block(block_type: u32, body: &[u8]) -> Vec<u8> {
+        let padded = body.len().div_ceil(4) * 4;
+        let total_length = (12 + padded) as u32;
+        let mut bytes = Vec::with_capacity(total_length as usize);
+        bytes.extend_from_slice(&block_type.to_le_bytes());
+        bytes.extend_from_slice(&total_length.to_le_bytes());
+        bytes.extend_from_slice(body);
+        bytes.resize(8 + padded, 0);
+        bytes.extend_from_slice(&total_length.to_le_bytes());
+        bytes
+    }
+
+    // 选项：代码 + 长度 + 值 (按4字节对齐)
+    fn option(bytes: &mut Vec<u8>, code: u16, value: &[u8]) {
+        bytes.extend_from_slice(&code.to_le_bytes());
+        bytes.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(value);
+        bytes.resize(bytes.len() + (4 - value.len() % 4) % 4, 0);
+    }
+
+    fn section_header_block() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        body.extend_from_slice(&1u16.to_le_bytes()); // 主版本
+        body.extend_from_slice(&0u16.to_le_bytes()); // 次版本
+        body.extend_from_slice(&(-1i64).to_le_bytes()); // 节长度未知
+        Self::option(&mut body, OPT_SHB_USERAPPL, b"rid-simulator-app");
+        Self::option(&mut body, OPT_END_OF_OPT, &[]);
+        Self::block(SECTION_HEADER_BLOCK, &body)
+    }
+
+    fn interface_description_block() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&LINKTYPE_RADIOTAP.to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes()); // 预留
+        body.extend_from_slice(&0u32.to_le_bytes()); // 不限制抓包长度
+        // 未写 if_tsresol，时间戳按默认的微秒精度解析
+        Self::option(&mut body, OPT_IF_NAME, b"rid-simulator");
+        Self::option(&mut body, OPT_END_OF_OPT, &[]);
+        Self::block(INTERFACE_DESCRIPTION_BLOCK, &body)
+    }
+
+    fn enhanced_packet_block(frame: &[u8], timestamp_micros: u64) -> Vec<u8> {
+        let mut body = Vec::with_capacity(20 + frame.len());
+        body.extend_from_slice(&0u32.to_le_bytes()); // 接口 ID
+        body.extend_from_slice(&((timestamp_micros >> 32) as u32).to_le_bytes());
+        body.extend_from_slice(&(timestamp_micros as u32).to_le_bytes());
+        body.extend_from_slice(&(frame.len() as u32).to_le_bytes()); // 抓包长度
+        body.extend_from_slice(&(frame.len() as u32).to_le_bytes()); // 原始长度
+        body.extend_from_slice(frame);
+        Self::block(ENHANCED_PACKET_BLOCK, &body)
+    }
+}
+
+impl RidTransport for PcapngWriter {
+    fn send_frame(&mut self, frame: &[u8], time: DateTime<Utc>) -> Result<(), String> {
+        let result = self.write_frame(frame, time);
+        self.health.record(&result);
+        result
+    }
+
+    /// 给出配置的路径，轮转后的文件见 `current_path`
+    fn describe(&self) -> String {
+        format!("pcapng:{}", self.path.display())
+    }
+
+    fn health(&self) -> TransportHealth {
+        self.health.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rid-pcapng-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("rid.pcapng")
+    }
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    // 按块类型和总长度拆分文件
+    fn blocks(bytes: &[u8]) -> Vec<(u32, &[u8])> {
+        let mut blocks = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let block_type = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+            let length = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap()) as usize;
+            let trailer = u32::from_le_bytes(bytes[offset + length - 4..offset + length].try_into().unwrap()) as usize;
+            assert_eq!(length, trailer);
+            assert_eq!(length % 4, 0);
+            blocks.push((block_type, &bytes[offset + 8..offset + length - 4]));
+            offset += length;
+        }
+        blocks
+    }
+
+    #[test]
+    fn writes_radiotap_packets_with_timestamps() {
+        let path = temp_path("basic");
+        let mut writer = PcapngWriter::create(&path, PcapngRotation::default()).unwrap();
+        writer.send_frame(&[1, 2, 3, 4, 5], start()).unwrap();
+        let time = start() + Duration::milliseconds(100);
+        writer.send_frame(&[6, 7, 8], time).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let blocks = blocks(&bytes);
+        assert_eq!(blocks.iter().map(|(t, _)| *t).collect::<Vec<_>>(), [SECTION_HEADER_BLOCK, 1, 6, 6]);
+        assert_eq!(&blocks[0].1[..4], &BYTE_ORDER_MAGIC.to_le_bytes());
+        assert_eq!(&blocks[1].1[..2], &LINKTYPE_RADIOTAP.to_le_bytes());
+
+        let packet = blocks[3].1;
+        let high = u32::from_le_bytes(packet[4..8].try_into().unwrap()) as u64;
+        let low = u32::from_le_bytes(packet[8..12].try_into().unwrap()) as u64;
+        assert_eq!((high << 32) | low, time.timestamp_micros() as u64);
+        assert_eq!(&packet[12..16], &3u32.to_le_bytes());
+        assert_eq!(&packet[20..23], &[6, 7, 8]);
+        assert_eq!(writer.health().frames_sent, 2);
+    }
+
+    #[test]
+    fn rotates_by_size_and_time() {
+        let path = temp_path("rotate");
+        let rotation = PcapngRotation { max_bytes: Some(200), max_duration: Some(Duration::seconds(10)) };
+        let mut writer = PcapngWriter::create(&path, rotation).unwrap();
+
+        // 每帧 32 + 60 字节，第二帧超出 200 字节
+        writer.send_frame(&[0u8; 60], start()).unwrap();
+        writer.send_frame(&[0u8; 60], start()).unwrap();
+        assert_eq!(writer.current_path(), path.with_file_name("rid-1.pcapng"));
+
+        writer.send_frame(&[0u8; 4], start() + Duration::seconds(10)).unwrap();
+        assert_eq!(writer.current_path(), path.with_file_name("rid-2.pcapng"));
+
+        for index in 0..3 {
+            let bytes = std::fs::read(PcapngWriter::indexed_path(&path, index)).unwrap();
+            let blocks = blocks(&bytes);
+            assert_eq!(blocks.len(), 3, "file {}", index);
+            assert_eq!(blocks[0].0, SECTION_HEADER_BLOCK);
+        }
+    }
+
+    #[test]
+    fn does_not_overwrite_existing_files() {
+        let path = temp_path("existing");
+        std::fs::write(&path, b"earlier capture").unwrap();
+        std::fs::write(path.with_file_name("rid-2.pcapng"), b"earlier capture").unwrap();
+
+        let rotation = PcapngRotation { max_bytes: Some(100), max_duration: None };
+        let mut writer = PcapngWriter::create(&path, rotation).unwrap();
+        assert_eq!(writer.current_path(), path.with_file_name("rid-1.pcapng"));
+        writer.send_frame(&[0u8; 60], start()).unwrap();
+        writer.send_frame(&[0u8; 60], start()).unwrap();
+        assert_eq!(writer.current_path(), path.with_file_name("rid-3.pcapng"));
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"earlier capture");
+        assert_eq!(std::fs::read(path.with_file_name("rid-2.pcapng")).unwrap(), b"earlier capture");
+    }
+}
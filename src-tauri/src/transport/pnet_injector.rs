@@ -0,0 +1,127 @@
+use std::fmt;
+use chrono::{DateTime, Utc};
+use pnet::datalink::{interfaces, Channel, DataLinkSender, NetworkInterface};
+use tracing::{info, warn, error};
+
+use super::{RidTransport, TransportHealth};
+
+/// 通过 pnet 向监听模式网卡注入帧
+///
+/// 通道在首次发送时打开并一直保持。打开或发送失败时关闭通道，按网卡名称重新查找网卡
+/// (拔插后索引可能变化) 并重开一次，仍失败时把错误返回给调用方，下一帧再重试
+pub struct PnetInjector {
+    interface: NetworkInterface,
+    tx: Option<Box<dyn DataLinkSender>>,
+    reopen_count: u64, // 通道重开次数
+    health: TransportHealth,
+}
+
+impl PnetInjector {
+    pub fn new(interface: NetworkInterface) -> Self {
+        Self {
+            interface,
+            tx: None,
+            reopen_count: 0,
+            health: TransportHealth::new(),
+        }
+    }
+
+    pub fn interface(&self) -> &NetworkInterface {
+        &self.interface
+    }
+
+    /// 通道重开次数
+    pub fn reopen_count(&self) -> u64 {
+        self.reopen_count
+    }
+
+    fn open(&self) -> Result<Box<dyn DataLinkSender>, String> {
+        match pnet::datalink::channel(&self.interface, Default::default()) {
+            Ok(Channel::Ethernet(tx, _rx)) => {
+                info!("channel opened: {}", self.interface.name);
+                Ok(tx)
+            },
+            Ok(_) => {
+                error!("Unsupported channel type");
+                Err("不支持的通道类型".into())
+            }
+            Err(e) => {
+                error!("Failed to create channel: {}", e);
+                Err(format!("通道创建失败: {}", e))
+            }
+        }
+    }
+
+    // 按名称重新查找网卡
+    fn refresh_interface(&mut self) -> Result<(), String> {
+        match interfaces().into_iter().find(|interface| interface.name == self.interface.name) {
+            Some(interface) => {
+                self.interface = interface;
+                Ok(())
+            }
+            None => Err(format!("网卡不存在: {}", self.interface.name)),
+        }
+    }
+
+    fn inject(&mut self, frame: &[u8]) -> Result<(), String> {
+        let tx = match self.tx.take() {
+            Some(tx) => tx,
+            None => self.open()?,
+        };
+        let tx = self.tx.insert(tx);
+        match tx.send_to(frame, None) {
+            Some(Ok(())) => Ok(()),
+            Some(Err(e)) => {
+                error!("Failed to send packet: {}", e);
+                self.tx = None;
+                Err(format!("发送失败: {}", e))
+            }
+            None => {
+                error!("Failed to send packet");
+                self.tx = None;
+                Err("发送失败".into())
+            }
+        }
+    }
+
+    fn reopen_and_inject(&mut self, frame: &[u8]) -> Result<(), String> {
+        self.reopen_count += 1;
+        warn!("reopening channel: {}", self.interface.name);
+        self.refresh_interface()?;
+        self.inject(frame)
+    }
+}
+
+impl RidTransport for PnetInjector {
+    fn send_frame(&mut self, frame: &[u8], _time: DateTime<Utc>) -> Result<(), String> {
+        // 失败时重新查找网卡并重开通道，再发一次
+        let result = match self.inject(frame) {
+            Ok(()) => Ok(()),
+            Err(_) => self.reopen_and_inject(frame),
+        };
+        self.health.record(&result);
+        result
+    }
+
+    fn describe(&self) -> String {
+        match self.interface.mac {
+            Some(mac) => format!("pnet:{} ({})", self.interface.name, mac),
+            None => format!("pnet:{}", self.interface.name),
+        }
+    }
+
+    fn health(&self) -> TransportHealth {
+        self.health.clone()
+    }
+}
+
+impl fmt::Debug for PnetInjector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PnetInjector")
+            .field("interface", &self.interface.name)
+            .field("open", &self.tx.is_some())
+            .field("reopen_count", &self.reopen_count)
+            .field("health", &self.health)
+            .finish()
+    }
+}
@@ -0,0 +1,263 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::{mpsc, oneshot};
+use tokio::sync::mpsc::error::{SendTimeoutError, TrySendError};
+use tracing::{error, warn};
+
+use super::{RidTransport, TransportHealth};
+
+/// 队列中的条目
+enum QueueItem {
+    Frame(Vec<u8>, DateTime<Utc>), // 帧及其生成时间
+    Flush(oneshot::Sender<()>), // 之前的帧全部处理完后回复
+}
+
+#[derive(Debug, Default)]
+struct QueueCounters {
+    enqueued: AtomicU64,
+    sent: AtomicU64,
+    failed: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// 发送队列的计数
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct QueueStats {
+    pub capacity: usize, // 队列容量 (帧)
+    pub pending: u64,    // 尚未发送的帧数
+    pub enqueued: u64,   // 累计入队的帧数
+    pub sent: u64,       // 累计发送成功的帧数
+    pub failed: u64,     // 累计发送失败的帧数
+    pub dropped: u64,    // 队列满而丢弃的帧数
+}
+
+/// 有界发送队列，由独立线程把帧交给发送通道
+///
+/// 发送通道归工作线程独占，在队列的整个生命周期内保持打开。通道说明在创建时取得，
+/// 运行状况由工作线程在每次发送后写入共享状态，查询时不会被阻塞中的发送拖住。入队时队列已满则最多等待 `max_wait`，
+/// 仍然没有空位时丢弃该帧并计数，避免网卡变慢时拖住 MQTT 事件循环
+pub struct SendQueue {
+    tx: mpsc::Sender<QueueItem>,
+    description: String,
+    health: Arc<Mutex<TransportHealth>>,
+    counters: Arc<QueueCounters>,
+    capacity: usize,
+    max_wait: Duration,
+}
+
+impl SendQueue {
+    /// 默认容量，可容纳数十架无人机 10 Hz 广播时一个周期内的帧
+    pub const DEFAULT_CAPACITY: usize = 256;
+    /// 默认的最长等待时间，即一个 10 Hz 广播周期
+    pub const DEFAULT_MAX_WAIT: Duration = Duration::from_millis(100);
+
+    pub fn new(transport: Box<dyn RidTransport>, capacity: usize, max_wait: Duration) -> Self {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        let description = transport.describe();
+        let health = Arc::new(Mutex::new(transport.health()));
+        let counters = Arc::new(QueueCounters::default());
+        // 队列关闭 (SendQueue 被丢弃) 后，工作线程处理完剩余的帧自行退出
+        {
+            let health = health.clone();
+            let counters = counters.clone();
+            std::thread::Builder::new()
+                .name("rid-send-queue".into())
+                .spawn(move || Self::run(rx, transport, health, counters))
+                .expect("failed to spawn send queue thread");
+        }
+        Self {
+            tx,
+            description,
+            health,
+            counters,
+            capacity: capacity.max(1),
+            max_wait,
+        }
+    }
+
+    fn run(
+        mut rx: mpsc::Receiver<QueueItem>,
+        mut transport: Box<dyn RidTransport>,
+        health: Arc<Mutex<TransportHealth>>,
+        counters: Arc<QueueCounters>,
+    ) {
+        while let Some(item) = rx.blocking_recv() {
+            match item {
+                QueueItem::Frame(frame, time) => {
+                    let result = transport.send_frame(&frame, time);
+                    *health.lock().unwrap() = transport.health();
+                    match result {
+                        Ok(()) => counters.sent.fetch_add(1, Ordering::Relaxed),
+                        Err(e) => {
+                            error!("Failed to send frame: {}", e);
+                            counters.failed.fetch_add(1, Ordering::Relaxed)
+                        }
+                    };
+                }
+                QueueItem::Flush(done) => {
+                    let _ = done.send(());
+                }
+            }
+        }
+    }
+
+    /// 入队一帧，队列满时最多等待 `max_wait`，超时后丢弃
+    pub async fn send(&self, frame: Vec<u8>, time: DateTime<Utc>) -> Result<(), String> {
+        match self.tx.send_timeout(QueueItem::Frame(frame, time), self.max_wait).await {
+            Ok(()) => {
+                self.counters.enqueued.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(SendTimeoutError::Timeout(_)) => Err(self.drop_frame()),
+            Err(SendTimeoutError::Closed(_)) => Err("发送队列已关闭".into()),
+        }
+    }
+
+    /// 不等待的入队，队列满时立即丢弃
+    pub fn try_send(&self, frame: Vec<u8>, time: DateTime<Utc>) -> Result<(), String> {
+        match self.tx.try_send(QueueItem::Frame(frame, time)) {
+            Ok(()) => {
+                self.counters.enqueued.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(TrySendError::Full(_)) => Err(self.drop_frame()),
+            Err(TrySendError::Closed(_)) => Err("发送队列已关闭".into()),
+        }
+    }
+
+    fn drop_frame(&self) -> String {
+        let dropped = self.counters.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        warn!("Send queue full, frame dropped (total {})", dropped);
+        format!("发送队列已满，丢弃帧 (累计 {} 帧)", dropped)
+    }
+
+    /// 等待此前入队的帧全部处理完
+    pub async fn flush(&self) {
+        let (done_tx, done_rx) = oneshot::channel();
+        if self.tx.send(QueueItem::Flush(done_tx)).await.is_ok() {
+            let _ = done_rx.await;
+        }
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        let enqueued = self.counters.enqueued.load(Ordering::Relaxed);
+        let sent = self.counters.sent.load(Ordering::Relaxed);
+        let failed = self.counters.failed.load(Ordering::Relaxed);
+        QueueStats {
+            capacity: self.capacity,
+            pending: enqueued.saturating_sub(sent + failed),
+            enqueued,
+            sent,
+            failed,
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 发送通道的说明，创建队列时取得
+    pub fn describe(&self) -> &str {
+        &self.description
+    }
+
+    /// 发送通道的运行状况，截至最近一次发送完成
+    pub fn health(&self) -> TransportHealth {
+        self.health.lock().unwrap().clone()
+    }
+}
+
+impl std::fmt::Debug for SendQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SendQueue")
+            .field("transport", &self.description)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemorySink;
+    use std::sync::mpsc as std_mpsc;
+
+    /// 收到第一帧后阻塞，直到测试放行
+    #[derive(Debug)]
+    struct StalledTransport {
+        started: std_mpsc::Sender<()>,
+        release: Mutex<std_mpsc::Receiver<()>>,
+    }
+
+    impl RidTransport for StalledTransport {
+        fn send_frame(&mut self, _frame: &[u8], _time: DateTime<Utc>) -> Result<(), String> {
+            let _ = self.started.send(());
+            let _ = self.release.lock().unwrap().recv();
+            Ok(())
+        }
+
+        fn describe(&self) -> String {
+            "stalled".to_string()
+        }
+
+        fn health(&self) -> TransportHealth {
+            TransportHealth::new()
+        }
+    }
+
+    #[tokio::test]
+    async fn delivers_frames_in_order() {
+        let sink = MemorySink::new();
+        let queue = SendQueue::new(Box::new(sink.clone()), 4, SendQueue::DEFAULT_MAX_WAIT);
+        for index in 0..20u8 {
+            queue.send(vec![index], Utc::now()).await.unwrap();
+        }
+        queue.flush().await;
+
+        assert_eq!(sink.frames(), (0..20u8).map(|index| vec![index]).collect::<Vec<_>>());
+        let stats = queue.stats();
+        assert_eq!((stats.enqueued, stats.sent, stats.pending, stats.dropped), (20, 20, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn drops_when_full_and_counts() {
+        let (started_tx, started_rx) = std_mpsc::channel();
+        let (release_tx, release_rx) = std_mpsc::channel();
+        let transport = StalledTransport { started: started_tx, release: Mutex::new(release_rx) };
+        let queue = SendQueue::new(Box::new(transport), 1, Duration::from_millis(10));
+
+        // 第一帧被工作线程取走并阻塞，第二帧占满队列
+        queue.send(vec![1], Utc::now()).await.unwrap();
+        started_rx.recv().unwrap();
+        queue.send(vec![2], Utc::now()).await.unwrap();
+
+        assert!(queue.send(vec![3], Utc::now()).await.is_err());
+        assert!(queue.try_send(vec![4], Utc::now()).is_err());
+        assert_eq!(queue.stats().dropped, 2);
+
+        release_tx.send(()).unwrap();
+        release_tx.send(()).unwrap();
+        queue.flush().await;
+        let stats = queue.stats();
+        assert_eq!((stats.enqueued, stats.sent, stats.pending), (2, 2, 0));
+    }
+
+    #[tokio::test]
+    async fn reports_status_while_transport_is_blocked() {
+        let (started_tx, started_rx) = std_mpsc::channel();
+        let (release_tx, release_rx) = std_mpsc::channel();
+        let transport = StalledTransport { started: started_tx, release: Mutex::new(release_rx) };
+        let queue = SendQueue::new(Box::new(transport), 4, SendQueue::DEFAULT_MAX_WAIT);
+
+        queue.send(vec![1], Utc::now()).await.unwrap();
+        started_rx.recv().unwrap();
+        // 工作线程仍在 send_frame 中，查询不能等待它
+        assert_eq!(queue.describe(), "stalled");
+        assert!(queue.health().healthy);
+        assert_eq!(queue.stats().pending, 1);
+
+        release_tx.send(()).unwrap();
+        queue.flush().await;
+        assert_eq!(queue.stats().sent, 1);
+    }
+}
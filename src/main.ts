@@ -5,6 +5,38 @@ let environmentSelectEl: HTMLSelectElement | null;
 let connectBtnEl: HTMLButtonElement | null;
 let connectionStatusEl: HTMLElement | null;
 let logDisplayEl: HTMLElement | null;
+let packetDisplayEl: HTMLElement | null;
+let interfaceSelectEl: HTMLSelectElement | null;
+let fanoutSelectEl: HTMLSelectElement | null;
+let transportDisplayEl: HTMLElement | null;
+
+// Candidate network interface, see InterfaceInfo in src-tauri/src/transport/interfaces.rs
+interface InterfaceInfo {
+  name: string;
+  mac?: string;
+  ips: string[];
+  up: boolean;
+  running: boolean;
+  wireless: boolean;
+  monitor: boolean;
+}
+
+// Per-interface counters, see TransportStats in src-tauri/src/transport/fanout.rs
+interface TransportStats {
+  transport: string;
+  health: { healthy: boolean; frames_sent: number; send_errors: number; last_error?: string };
+  queue: { capacity: number; pending: number; enqueued: number; sent: number; failed: number; dropped: number };
+  drones: number;
+}
+
+// Field tree of a sent packet, see FieldNode in src-tauri/src/message/describe.rs
+interface FieldNode {
+  key: string;
+  label: string;
+  value?: string;
+  raw?: string;
+  children?: FieldNode[];
+}
 
 // Connection state
 let isConnected = false;
@@ -32,6 +64,83 @@ function addLog(message: string) {
   }
 }
 
+// Render a field tree as indented text, raw wire values in brackets
+function formatFieldNode(node: FieldNode, depth = 0): string {
+  let line = `${"  ".repeat(depth)}${node.label}`;
+  if (node.value !== undefined) {
+    line += `: ${node.value}`;
+  }
+  if (node.raw !== undefined) {
+    line += ` [${node.raw}]`;
+  }
+  const children = (node.children ?? []).map((child) => formatFieldNode(child, depth + 1));
+  return [line, ...children].join("\n");
+}
+
+// Show the most recently sent packet
+function showPacket(node: FieldNode) {
+  if (packetDisplayEl) {
+    packetDisplayEl.textContent = formatFieldNode(node);
+  }
+}
+
+// Fill the interface list, keeping the current choices that still exist
+async function refreshInterfaces() {
+  if (!interfaceSelectEl) return;
+
+  try {
+    const interfaces = await invoke("list_interfaces") as InterfaceInfo[];
+    const selected = new Set(Array.from(interfaceSelectEl.selectedOptions, (option) => option.value));
+    interfaceSelectEl.replaceChildren();
+    for (const info of interfaces) {
+      const state = [
+        info.up ? "启用" : "未启用",
+        info.wireless ? "无线" : "有线",
+        ...(info.monitor ? ["监听模式"] : []),
+      ].join(", ");
+      interfaceSelectEl.add(new Option(`${info.name} ${info.mac ?? ""} (${state})`, info.name, false, selected.has(info.name)));
+    }
+  } catch (error) {
+    addLog(`获取网卡列表失败: ${error}`);
+  }
+}
+
+// Use the chosen interfaces and fan-out mode for injection
+async function handleSelectInterface() {
+  if (!interfaceSelectEl || !fanoutSelectEl) return;
+
+  try {
+    const mode = await invoke("set_fanout_mode", { mode: fanoutSelectEl.value });
+    addLog(`分发方式: ${mode}`);
+
+    const names = Array.from(interfaceSelectEl.selectedOptions, (option) => option.value);
+    if (names.length === 0) {
+      addLog("未指定网卡，连接时自动选择");
+      return;
+    }
+    const result = await invoke("select_interface", { selector: names.join(",") }) as string[];
+    addLog(`已选择网卡: ${result.join(", ")}`);
+  } catch (error) {
+    addLog(`选择网卡失败: ${error}`);
+  }
+}
+
+// Show per-interface counters while connected
+async function refreshTransportStats() {
+  if (!transportDisplayEl || !isConnected) return;
+
+  try {
+    const stats = await invoke("get_transport_stats") as TransportStats[];
+    transportDisplayEl.textContent = stats.length === 0 ? "暂无数据" : stats.map((item) => {
+      const state = item.health.healthy ? "正常" : `异常 (${item.health.last_error ?? ""})`;
+      return `${item.transport}: ${state}, 无人机 ${item.drones}, 已发送 ${item.queue.sent}, ` +
+        `失败 ${item.queue.failed}, 丢弃 ${item.queue.dropped}, 排队 ${item.queue.pending}/${item.queue.capacity}`;
+    }).join("\n");
+  } catch (error) {
+    console.error("Failed to get transport stats:", error);
+  }
+}
+
 // Update connection status display
 function updateConnectionStatus(status: string, connected: boolean) {
   if (connectionStatusEl) {
@@ -88,6 +197,15 @@ window.addEventListener("DOMContentLoaded", () => {
   connectBtnEl = document.querySelector("#connect-btn");
   connectionStatusEl = document.querySelector("#connection-status");
   logDisplayEl = document.querySelector("#log-display");
+  packetDisplayEl = document.querySelector("#packet-display");
+  interfaceSelectEl = document.querySelector("#interface-select");
+  fanoutSelectEl = document.querySelector("#fanout-select");
+  transportDisplayEl = document.querySelector("#transport-display");
+
+  document.querySelector("#interface-refresh-btn")?.addEventListener("click", refreshInterfaces);
+  document.querySelector("#interface-apply-btn")?.addEventListener("click", handleSelectInterface);
+  refreshInterfaces();
+  setInterval(refreshTransportStats, 1000);
 
   // Add event listener for connect button
   connectBtnEl?.addEventListener("click", () => {
@@ -104,6 +222,9 @@ window.addEventListener("DOMContentLoaded", () => {
       const message = event.payload as string;
       addLog(message);
     });
+    listen('rid-packet', (event) => {
+      showPacket(event.payload as FieldNode);
+    });
   }).catch(error => {
     console.error('Failed to set up Rust log listener:', error);
   });